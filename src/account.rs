@@ -0,0 +1,388 @@
+use crate::{
+    balance::{project_balance, BalanceProjection},
+    money::Money,
+    transaction::{affordability_report, AffordabilityReport, TransactionError, TransactionModel},
+};
+use chrono::{Date, Utc};
+use log::debug;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A single account, such as a salary, bills or offset account, and the
+/// [`TransactionModel`]s that are paid into and out of it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Account {
+    name: String,
+    revenues: Vec<TransactionModel>,
+    expenses: Vec<TransactionModel>,
+    savings: Vec<TransactionModel>,
+}
+
+/// Identifies an [`Account`] within [`Accounts`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountId(usize);
+
+/// A [`TransactionModel`] that moves money from one account to another.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transfer {
+    from: AccountId,
+    to: AccountId,
+    model: TransactionModel,
+}
+
+/// A collection of [`Account`]s and the [`Transfer`]s between them.
+///
+/// Affordability and balances can be calculated for each account, where a transfer is
+/// an expense of the account it comes from and a revenue of the account it goes to, or
+/// for every account in aggregate, where transfers cancel each other out.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Accounts {
+    accounts: Vec<Account>,
+    transfers: Vec<Transfer>,
+}
+
+/// Errors encountered whilst working with [`Accounts`].
+#[derive(Error, Debug, PartialEq)]
+pub enum AccountError {
+    #[error(transparent)]
+    Transaction(#[from] TransactionError),
+    #[error("there is no account {0:?}")]
+    UnknownAccount(AccountId),
+    #[error("cannot transfer from account {0:?} to itself")]
+    SelfTransfer(AccountId),
+}
+
+impl Account {
+    /// Create a new `Account` with no transactions.
+    pub fn new(name: &str) -> Self {
+        Account {
+            name: name.into(),
+            revenues: Vec::new(),
+            expenses: Vec::new(),
+            savings: Vec::new(),
+        }
+    }
+
+    /// Add a revenue that is paid into this account.
+    pub fn add_revenue(&mut self, model: TransactionModel) {
+        self.revenues.push(model);
+    }
+
+    /// Add an expense that is paid from this account.
+    pub fn add_expense(&mut self, model: TransactionModel) {
+        self.expenses.push(model);
+    }
+
+    /// Add savings that are paid from this account.
+    pub fn add_savings(&mut self, model: TransactionModel) {
+        self.savings.push(model);
+    }
+
+    /// The name of this account.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// The revenues paid into this account.
+    pub fn get_revenues(&self) -> &[TransactionModel] {
+        &self.revenues
+    }
+
+    /// The expenses paid from this account.
+    pub fn get_expenses(&self) -> &[TransactionModel] {
+        &self.expenses
+    }
+
+    /// The savings paid from this account.
+    pub fn get_savings(&self) -> &[TransactionModel] {
+        &self.savings
+    }
+}
+
+impl Transfer {
+    /// The account that money is transferred from.
+    pub fn get_from(&self) -> AccountId {
+        self.from
+    }
+
+    /// The account that money is transferred to.
+    pub fn get_to(&self) -> AccountId {
+        self.to
+    }
+
+    /// The model of the transferred payments.
+    pub fn get_model(&self) -> &TransactionModel {
+        &self.model
+    }
+}
+
+impl Accounts {
+    pub fn new() -> Self {
+        Accounts::default()
+    }
+
+    /// Add an account, returning the ID used to refer to it.
+    pub fn add_account(&mut self, account: Account) -> AccountId {
+        self.accounts.push(account);
+        AccountId(self.accounts.len() - 1)
+    }
+
+    /// Add a transfer of the `model`'s payments from one account to another.
+    pub fn add_transfer(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        model: TransactionModel,
+    ) -> Result<(), AccountError> {
+        self.get_account(from)?;
+        self.get_account(to)?;
+        if from == to {
+            return Err(AccountError::SelfTransfer(from));
+        }
+
+        self.transfers.push(Transfer { from, to, model });
+        Ok(())
+    }
+
+    /// The account with the given ID.
+    pub fn get_account(&self, id: AccountId) -> Result<&Account, AccountError> {
+        self.accounts
+            .get(id.0)
+            .ok_or(AccountError::UnknownAccount(id))
+    }
+
+    /// The account with the given ID, for adding transactions to.
+    pub fn get_account_mut(&mut self, id: AccountId) -> Result<&mut Account, AccountError> {
+        self.accounts
+            .get_mut(id.0)
+            .ok_or(AccountError::UnknownAccount(id))
+    }
+
+    /// Every account, in the order they were added.
+    pub fn get_accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// Every transfer between accounts, in the order they were added.
+    pub fn get_transfers(&self) -> &[Transfer] {
+        &self.transfers
+    }
+
+    /// Calculate the net amount for each day that the given account's transactions and
+    /// transfers contribute to. See [`affordability_report`] for details.
+    pub fn affordability_report(&self, id: AccountId) -> Result<AffordabilityReport, AccountError> {
+        debug!("calculating affordability for account {:?}", id);
+
+        let (revenues, expenses, savings) = self.get_models(id)?;
+        Ok(affordability_report(
+            Some(&revenues),
+            Some(&expenses),
+            Some(&savings),
+        )?)
+    }
+
+    /// Calculate the net amount for each day that every account's transactions
+    /// contribute to. Transfers between accounts are excluded, as they don't change the
+    /// aggregate.
+    pub fn aggregate_affordability_report(&self) -> Result<AffordabilityReport, AccountError> {
+        debug!("calculating aggregate affordability");
+
+        let (revenues, expenses, savings) = self.get_aggregate_models();
+        Ok(affordability_report(
+            Some(&revenues),
+            Some(&expenses),
+            Some(&savings),
+        )?)
+    }
+
+    /// Project the running balance of the given account from `start` to `end`
+    /// (inclusive). See [`project_balance`] for details.
+    pub fn project_balance(
+        &self,
+        id: AccountId,
+        opening_balance: Money,
+        start: Date<Utc>,
+        end: Date<Utc>,
+    ) -> Result<BalanceProjection, AccountError> {
+        let (revenues, expenses, savings) = self.get_models(id)?;
+        Ok(project_balance(
+            opening_balance,
+            start,
+            end,
+            Some(&revenues),
+            Some(&expenses),
+            Some(&savings),
+        )?)
+    }
+
+    /// Project the combined running balance of every account from `start` to `end`
+    /// (inclusive), where the `opening_balance` is the total of every account's
+    /// opening balance.
+    pub fn project_aggregate_balance(
+        &self,
+        opening_balance: Money,
+        start: Date<Utc>,
+        end: Date<Utc>,
+    ) -> Result<BalanceProjection, AccountError> {
+        let (revenues, expenses, savings) = self.get_aggregate_models();
+        Ok(project_balance(
+            opening_balance,
+            start,
+            end,
+            Some(&revenues),
+            Some(&expenses),
+            Some(&savings),
+        )?)
+    }
+
+    // Get the revenues, expenses and savings of an account, including transfers in as
+    // revenues and transfers out as expenses
+    fn get_models(&self, id: AccountId) -> Result<Models, AccountError> {
+        let account = self.get_account(id)?;
+
+        let mut revenues = account.revenues.clone();
+        let mut expenses = account.expenses.clone();
+        for transfer in &self.transfers {
+            if transfer.to == id {
+                revenues.push(transfer.model.clone());
+            }
+            if transfer.from == id {
+                expenses.push(transfer.model.clone());
+            }
+        }
+
+        Ok((revenues, expenses, account.savings.clone()))
+    }
+
+    // Get the revenues, expenses and savings of every account
+    fn get_aggregate_models(&self) -> Models {
+        let collect = |f: fn(&Account) -> &[TransactionModel]| {
+            self.accounts.iter().flat_map(f).cloned().collect()
+        };
+
+        (
+            collect(Account::get_revenues),
+            collect(Account::get_expenses),
+            collect(Account::get_savings),
+        )
+    }
+}
+
+type Models = (
+    Vec<TransactionModel>,
+    Vec<TransactionModel>,
+    Vec<TransactionModel>,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        currency::Currency,
+        frequency::Frequency,
+        transaction::{AffordabilityResult, Termination},
+        FixedClock, RoundingPolicy,
+    };
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn daily(value: Decimal) -> TransactionModel {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        TransactionModel::new_with_clock(
+            Money::new(value, Currency::AUD),
+            None,
+            Frequency::Daily(1),
+            Utc.ymd(2000, 4, 1),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap()
+    }
+
+    // A salary account that pays for a bills account
+    fn accounts() -> (Accounts, AccountId, AccountId) {
+        let mut salary = Account::new("Salary");
+        salary.add_revenue(daily(dec!(100)));
+        salary.add_savings(daily(dec!(10)));
+        let mut bills = Account::new("Bills");
+        bills.add_expense(daily(dec!(60)));
+
+        let mut accounts = Accounts::new();
+        let salary = accounts.add_account(salary);
+        let bills = accounts.add_account(bills);
+        (accounts, salary, bills)
+    }
+
+    #[test]
+    fn accounts_affordability() {
+        let (mut accounts, salary, bills) = accounts();
+        let today = Utc.ymd(2000, 4, 1);
+
+        // Without a transfer, the bills account can't pay its expenses
+        assert_eq!(
+            accounts.affordability_report(bills).unwrap().to_result(),
+            AffordabilityResult::Deficit(vec![today], Vec::new())
+        );
+
+        accounts
+            .add_transfer(salary, bills, daily(dec!(60)))
+            .unwrap();
+        assert_eq!(
+            accounts.affordability_report(bills).unwrap().to_result(),
+            AffordabilityResult::Balanced
+        );
+        assert_eq!(
+            accounts
+                .affordability_report(salary)
+                .unwrap()
+                .get_net(today),
+            Some(dec!(30))
+        );
+
+        // Transfers cancel out in aggregate
+        let aggregate = accounts.aggregate_affordability_report().unwrap();
+        assert_eq!(aggregate.get_net(today), Some(dec!(30)));
+    }
+
+    #[test]
+    fn accounts_balances() {
+        let (mut accounts, salary, bills) = accounts();
+        accounts
+            .add_transfer(salary, bills, daily(dec!(50)))
+            .unwrap();
+        let start = Utc.ymd(2000, 4, 1);
+        let end = Utc.ymd(2000, 4, 10);
+
+        let projection = accounts
+            .project_balance(bills, Money::new(dec!(50), Currency::AUD), start, end)
+            .unwrap();
+        assert_eq!(projection.get_closing_balance(), dec!(-50));
+        assert_eq!(projection.get_depletion_date(), Some(Utc.ymd(2000, 4, 6)));
+
+        let projection = accounts
+            .project_aggregate_balance(Money::new(dec!(50), Currency::AUD), start, end)
+            .unwrap();
+        assert_eq!(projection.get_closing_balance(), dec!(350));
+    }
+
+    #[test]
+    fn accounts_invalid_transfer() {
+        let (mut accounts, salary, _) = accounts();
+
+        assert_eq!(
+            accounts.add_transfer(salary, salary, daily(dec!(10))),
+            Err(AccountError::SelfTransfer(salary))
+        );
+        assert_eq!(
+            accounts.add_transfer(salary, AccountId(5), daily(dec!(10))),
+            Err(AccountError::UnknownAccount(AccountId(5)))
+        );
+    }
+}
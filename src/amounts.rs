@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use crate::frequency::Frequency;
+use chrono::{Date, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The amounts of a `TransactionModel`'s payments, where they vary between payments.
+///
+/// Any payment that doesn't have an amount in the schedule is for the model's value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AmountSchedule {
+    /// The amounts of the payments on the given dates, e.g. quarterly utility bills that
+    /// vary by season. Dates that aren't payment dates are ignored.
+    Dates(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date_map"))]
+        BTreeMap<Date<Utc>, Decimal>,
+    ),
+    /// The amounts of each payment in turn, starting with the first payment, e.g. a
+    /// subscription with an introductory price
+    Occurrences(Vec<Decimal>),
+}
+
+impl AmountSchedule {
+    /// Create a schedule for the first `count` payments, where each payment's amount is
+    /// a function of its index (starting from 0).
+    pub fn from_fn<F: Fn(usize) -> Decimal>(count: usize, f: F) -> Self {
+        AmountSchedule::Occurrences((0..count).map(f).collect())
+    }
+
+    // Get the amount of every payment in the schedule, keyed by payment date, for a
+    // model that starts on `start`
+    pub(crate) fn get_amounts(
+        &self,
+        frequency: &Frequency,
+        start: Date<Utc>,
+    ) -> BTreeMap<Date<Utc>, Decimal> {
+        match self {
+            AmountSchedule::Dates(amounts) => {
+                let last = match amounts.keys().next_back() {
+                    Some(date) => *date,
+                    None => return BTreeMap::new(),
+                };
+
+                frequency
+                    .occurrences(start)
+                    .take_while(|d| *d <= last)
+                    .filter_map(|d| amounts.get(&d).map(|a| (d, *a)))
+                    .collect()
+            }
+            AmountSchedule::Occurrences(amounts) => frequency
+                .occurrences(start)
+                .zip(amounts.iter().copied())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn get_amounts_dates() {
+        let schedule = AmountSchedule::Dates(
+            vec![
+                (Utc.ymd(2000, 4, 1), dec!(120)),
+                // Not a payment date
+                (Utc.ymd(2000, 5, 2), dec!(90)),
+                (Utc.ymd(2000, 7, 1), dec!(80)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let amounts =
+            schedule.get_amounts(&Frequency::MonthlyDate(3, vec![1]), Utc.ymd(2000, 4, 1));
+
+        assert_eq!(
+            amounts.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Utc.ymd(2000, 4, 1), dec!(120)),
+                (Utc.ymd(2000, 7, 1), dec!(80))
+            ]
+        );
+    }
+
+    #[test]
+    fn get_amounts_occurrences() {
+        let schedule = AmountSchedule::from_fn(3, |i| Decimal::from(i as u32 + 1) * dec!(10));
+        let amounts = schedule.get_amounts(&Frequency::Weekly(1, vec![6]), Utc.ymd(2000, 4, 1));
+
+        assert_eq!(
+            amounts.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Utc.ymd(2000, 4, 1), dec!(10)),
+                (Utc.ymd(2000, 4, 8), dec!(20)),
+                (Utc.ymd(2000, 4, 15), dec!(30))
+            ]
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{
+    currency::Currency,
+    money::Money,
+    transaction::{
+        acc_daily_contributions, collect_contributions, get_common_currency, TransactionError,
+        TransactionModel,
+    },
+};
+use chrono::{Date, Utc};
+use log::debug;
+use rust_decimal::Decimal;
+
+/// A day-by-day projection of an account balance. See [`project_balance`] for details.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BalanceProjection {
+    currency: Currency,
+    opening_balance: Decimal,
+    balances: Vec<(Date<Utc>, Decimal)>,
+}
+
+impl BalanceProjection {
+    /// The currency that every balance in this projection is in.
+    pub fn get_currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// The balance before any contributions were applied.
+    pub fn get_opening_balance(&self) -> Decimal {
+        self.opening_balance
+    }
+
+    /// The balance at the end of the final day of the projection.
+    pub fn get_closing_balance(&self) -> Decimal {
+        self.balances
+            .last()
+            .map(|(_, b)| *b)
+            .unwrap_or(self.opening_balance)
+    }
+
+    /// The balance at the end of each day of the projection, in date order.
+    pub fn get_balances(&self) -> &[(Date<Utc>, Decimal)] {
+        &self.balances
+    }
+
+    /// The balance at the end of the given day, if it falls within the projection.
+    pub fn get_balance(&self, date: Date<Utc>) -> Option<Decimal> {
+        self.balances
+            .binary_search_by_key(&date, |(d, _)| *d)
+            .ok()
+            .map(|i| self.balances[i].1)
+    }
+
+    /// The first day that the balance falls below zero, if any.
+    pub fn get_depletion_date(&self) -> Option<Date<Utc>> {
+        self.balances
+            .iter()
+            .find(|(_, b)| *b < Decimal::ZERO)
+            .map(|(d, _)| *d)
+    }
+
+    /// The day with the lowest balance. If several days share the lowest balance, the
+    /// earliest day is returned.
+    pub fn get_lowest_balance(&self) -> Option<(Date<Utc>, Decimal)> {
+        self.balances.iter().min_by_key(|(_, b)| *b).copied()
+    }
+}
+
+/// Project the running balance of an account from `start` to `end` (inclusive).
+///
+/// Each day's balance is the previous day's balance, plus that day's revenue
+/// contributions, less that day's expense and savings contributions. As contributions
+/// smooth payments over time, this is the balance of money that has not yet been
+/// allocated, rather than the balance a bank statement would show.
+///
+/// Use [`BalanceProjection::get_depletion_date`] to find the first day that the
+/// `opening_balance` is exhausted.
+///
+/// Every model must be in the same currency as the `opening_balance`.
+pub fn project_balance(
+    opening_balance: Money,
+    start: Date<Utc>,
+    end: Date<Utc>,
+    revenues: Option<&[TransactionModel]>,
+    expenses: Option<&[TransactionModel]>,
+    savings: Option<&[TransactionModel]>,
+) -> Result<BalanceProjection, TransactionError> {
+    debug!(
+        "projecting balance of {} from {} to {}",
+        opening_balance, start, end
+    );
+
+    let currency = opening_balance.get_currency();
+    if let Some(c) = get_common_currency(revenues, expenses, savings)? {
+        opening_balance.check_currency(c)?;
+    }
+
+    let opening_balance = opening_balance.get_amount();
+    let contributions = collect_contributions(revenues, expenses, savings);
+    let day_totals = acc_daily_contributions(&contributions, Some((start, end)));
+
+    let mut balance = opening_balance;
+    let balances = day_totals
+        .into_iter()
+        .map(|(date, total)| {
+            balance += total;
+            (date, balance)
+        })
+        .collect();
+
+    Ok(BalanceProjection {
+        currency,
+        opening_balance,
+        balances,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CurrencyError, Frequency};
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn daily(value: Decimal, start: Date<Utc>) -> TransactionModel {
+        TransactionModel::new(
+            Money::new(value, Currency::AUD),
+            None,
+            Frequency::Daily(1),
+            start,
+            None,
+            Some(start),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn project_balance_depletes() {
+        let start = Utc.ymd(2000, 4, 1);
+        let revenues = vec![daily(dec!(10), start)];
+        let expenses = vec![daily(dec!(12), start)];
+        let savings = vec![daily(dec!(3), start)];
+
+        let projection = project_balance(
+            Money::new(dec!(20), Currency::AUD),
+            start,
+            start + Duration::days(9),
+            Some(&revenues),
+            Some(&expenses),
+            Some(&savings),
+        )
+        .unwrap();
+
+        assert_eq!(projection.get_balances().len(), 10);
+        assert_eq!(projection.get_opening_balance(), dec!(20));
+        assert_eq!(projection.get_balance(start), Some(dec!(15)));
+        assert_eq!(projection.get_closing_balance(), dec!(-30));
+        assert_eq!(projection.get_depletion_date(), Some(Utc.ymd(2000, 4, 5)));
+        assert_eq!(
+            projection.get_lowest_balance(),
+            Some((Utc.ymd(2000, 4, 10), dec!(-30)))
+        );
+    }
+
+    #[test]
+    fn project_balance_sustainable() {
+        let start = Utc.ymd(2000, 4, 1);
+        let revenues = vec![daily(dec!(10), start)];
+        let expenses = vec![daily(dec!(5), start.succ())];
+
+        let projection = project_balance(
+            Money::zero(Currency::AUD),
+            start,
+            start + Duration::days(2),
+            Some(&revenues),
+            Some(&expenses),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            projection.get_balances(),
+            &[
+                (start, dec!(10)),
+                (Utc.ymd(2000, 4, 2), dec!(15)),
+                (Utc.ymd(2000, 4, 3), dec!(20)),
+            ]
+        );
+        assert_eq!(projection.get_depletion_date(), None);
+    }
+
+    #[test]
+    fn project_balance_empty_range() {
+        let start = Utc.ymd(2000, 4, 1);
+        let projection = project_balance(
+            Money::new(dec!(5), Currency::AUD),
+            start,
+            start.pred(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(projection.get_balances().is_empty());
+        assert_eq!(projection.get_closing_balance(), dec!(5));
+        assert_eq!(projection.get_lowest_balance(), None);
+    }
+
+    #[test]
+    fn project_balance_mixed_currencies() {
+        let start = Utc.ymd(2000, 4, 1);
+        let revenues = vec![daily(dec!(10), start)];
+
+        assert_eq!(
+            project_balance(
+                Money::new(dec!(5), Currency::USD),
+                start,
+                start,
+                Some(&revenues),
+                None,
+                None,
+            ),
+            Err(TransactionError::Currency(CurrencyError::Mismatch(
+                Currency::USD,
+                Currency::AUD
+            )))
+        );
+    }
+}
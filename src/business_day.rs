@@ -0,0 +1,154 @@
+use crate::holiday::HolidayCalendar;
+use chrono::{Date, Datelike, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The convention used to roll a payment date that doesn't fall on a business day.
+///
+/// Business days are Monday to Friday, excluding any holidays in a [`HolidayCalendar`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BusinessDayConvention {
+    /// Payments are made on the calendar date, even if it isn't a business day
+    Unadjusted,
+    /// Payments are made on the following business day
+    Following,
+    /// Payments are made on the following business day, unless that day falls in the
+    /// next month, in which case they are made on the preceding business day
+    ModifiedFollowing,
+    /// Payments are made on the preceding business day
+    Preceding,
+    /// Payments are made on the preceding business day, unless that day falls in the
+    /// previous month, in which case they are made on the following business day
+    ModifiedPreceding,
+}
+
+impl BusinessDayConvention {
+    /// Roll the given date to a business day according to this convention, ignoring
+    /// holidays.
+    pub fn adjust(&self, date: Date<Utc>) -> Date<Utc> {
+        self.adjust_for(date, &HolidayCalendar::default())
+    }
+
+    /// Roll the given date to a business day according to this convention, treating
+    /// holidays in the calendar as non-business days.
+    pub fn adjust_for(&self, date: Date<Utc>, calendar: &HolidayCalendar) -> Date<Utc> {
+        match *self {
+            BusinessDayConvention::Unadjusted => date,
+            BusinessDayConvention::Following => following(date, calendar),
+            BusinessDayConvention::Preceding => preceding(date, calendar),
+            BusinessDayConvention::ModifiedFollowing => {
+                let d = following(date, calendar);
+                if d.month() == date.month() {
+                    d
+                } else {
+                    preceding(date, calendar)
+                }
+            }
+            BusinessDayConvention::ModifiedPreceding => {
+                let d = preceding(date, calendar);
+                if d.month() == date.month() {
+                    d
+                } else {
+                    following(date, calendar)
+                }
+            }
+        }
+    }
+}
+
+fn following(mut date: Date<Utc>, calendar: &HolidayCalendar) -> Date<Utc> {
+    while !calendar.is_business_day(date) {
+        date = date.succ();
+    }
+    date
+}
+
+fn preceding(mut date: Date<Utc>, calendar: &HolidayCalendar) -> Date<Utc> {
+    while !calendar.is_business_day(date) {
+        date = date.pred();
+    }
+    date
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holiday::{Holiday, HolidayRule};
+    use chrono::TimeZone;
+
+    #[test]
+    fn adjust_business_day() {
+        let date = Utc.ymd(2000, 4, 5); // Wednesday
+        assert_eq!(BusinessDayConvention::Following.adjust(date), date);
+        assert_eq!(BusinessDayConvention::Preceding.adjust(date), date);
+    }
+
+    #[test]
+    fn adjust_unadjusted() {
+        let date = Utc.ymd(2000, 4, 1); // Saturday
+        assert_eq!(BusinessDayConvention::Unadjusted.adjust(date), date);
+    }
+
+    #[test]
+    fn adjust_following() {
+        let date = Utc.ymd(2000, 4, 1); // Saturday
+        assert_eq!(
+            BusinessDayConvention::Following.adjust(date),
+            Utc.ymd(2000, 4, 3)
+        );
+    }
+
+    #[test]
+    fn adjust_modified_following() {
+        let date = Utc.ymd(2000, 9, 30); // Saturday
+        assert_eq!(
+            BusinessDayConvention::ModifiedFollowing.adjust(date),
+            Utc.ymd(2000, 9, 29)
+        );
+        assert_eq!(
+            BusinessDayConvention::ModifiedFollowing.adjust(Utc.ymd(2000, 4, 1)),
+            Utc.ymd(2000, 4, 3)
+        );
+    }
+
+    #[test]
+    fn adjust_preceding() {
+        let date = Utc.ymd(2000, 4, 2); // Sunday
+        assert_eq!(
+            BusinessDayConvention::Preceding.adjust(date),
+            Utc.ymd(2000, 3, 31)
+        );
+    }
+
+    #[test]
+    fn adjust_modified_preceding() {
+        let date = Utc.ymd(2000, 4, 1); // Saturday
+        assert_eq!(
+            BusinessDayConvention::ModifiedPreceding.adjust(date),
+            Utc.ymd(2000, 4, 3)
+        );
+        assert_eq!(
+            BusinessDayConvention::ModifiedPreceding.adjust(Utc.ymd(2000, 4, 30)),
+            Utc.ymd(2000, 4, 28)
+        );
+    }
+
+    #[test]
+    fn adjust_for_holidays() {
+        let calendar = HolidayCalendar::new(vec![
+            Holiday::new("Good Friday", HolidayRule::Easter(-2)),
+            Holiday::new("Easter Monday", HolidayRule::Easter(1)),
+        ]);
+        let date = Utc.ymd(2000, 4, 23); // Easter Sunday
+
+        assert_eq!(
+            BusinessDayConvention::Following.adjust_for(date, &calendar),
+            Utc.ymd(2000, 4, 25)
+        );
+        assert_eq!(
+            BusinessDayConvention::Preceding.adjust_for(date, &calendar),
+            Utc.ymd(2000, 4, 20)
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::cell::Cell;
+
+use chrono::{Date, Duration, Utc};
+
+/// A source for the current date.
+///
+/// Calculations that depend on the current date (such as the lead time available to
+/// contribute towards a payment) read it from a `Clock`, so that they can be reproduced
+/// by fixing the date.
+pub trait Clock {
+    /// The current date.
+    fn today(&self) -> Date<Utc>;
+}
+
+/// A [`Clock`] that reads the current date from the system time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+/// A [`Clock`] that always returns the same date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(Date<Utc>);
+
+/// A [`Clock`] that only moves when it is told to, which is useful for testing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SteppingClock(Cell<Date<Utc>>);
+
+impl Clock for SystemClock {
+    fn today(&self) -> Date<Utc> {
+        Utc::today()
+    }
+}
+
+impl FixedClock {
+    pub fn new(date: Date<Utc>) -> Self {
+        FixedClock(date)
+    }
+}
+
+impl Clock for FixedClock {
+    fn today(&self) -> Date<Utc> {
+        self.0
+    }
+}
+
+impl SteppingClock {
+    pub fn new(date: Date<Utc>) -> Self {
+        SteppingClock(Cell::new(date))
+    }
+
+    /// Move the clock by the given duration, which may be negative.
+    pub fn advance(&self, duration: Duration) {
+        self.0.set(self.0.get() + duration);
+    }
+
+    /// Move the clock to the given date.
+    pub fn set(&self, date: Date<Utc>) {
+        self.0.set(date);
+    }
+}
+
+impl Clock for SteppingClock {
+    fn today(&self) -> Date<Utc> {
+        self.0.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fixed_clock_today() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        assert_eq!(clock.today(), Utc.ymd(2000, 4, 1));
+    }
+
+    #[test]
+    fn stepping_clock_advance() {
+        let clock = SteppingClock::new(Utc.ymd(2000, 2, 28));
+
+        clock.advance(Duration::days(1));
+        assert_eq!(clock.today(), Utc.ymd(2000, 2, 29));
+
+        clock.advance(Duration::days(-30));
+        assert_eq!(clock.today(), Utc.ymd(2000, 1, 30));
+
+        clock.set(Utc.ymd(2001, 1, 1));
+        assert_eq!(clock.today(), Utc.ymd(2001, 1, 1));
+    }
+}
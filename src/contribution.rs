@@ -1,17 +1,42 @@
-use crate::frequency::Frequency;
+use std::collections::BTreeMap;
+
+use crate::{currency::Currency, frequency::Frequency, money::Money, rounding::RoundingPolicy};
 use chrono::{Date, Duration, Utc};
 use log::{debug, error, trace};
 use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// The daily amount to contribute to an upcoming payment.
-#[derive(Debug, PartialEq)]
+///
+/// A `Contribution` is a read-only segment of a `TransactionModel`'s contributions,
+/// covering every day from its start date until its end date (or forever). Each day in
+/// a period contributes the regular amount, except the last day of the period, which may
+/// contribute a different amount to absorb any rounding. These amounts are then rounded
+/// according to the contribution's [`RoundingPolicy`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Contribution {
     regular: Decimal,
     last: Option<Decimal>,
+    currency: Currency,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date"))]
     start_date: Date<Utc>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::option_date"))]
     end_date: Option<Date<Utc>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration"))]
     period_length: Duration,
+    #[cfg_attr(feature = "serde", serde(default))]
+    rounding: RoundingPolicy,
+}
+
+// The amount of each payment that a contribution covers. Every payment is for `value`,
+// unless `amounts` has a different amount for its date.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PaymentAmounts<'a> {
+    value: Money,
+    amounts: Option<&'a BTreeMap<Date<Utc>, Decimal>>,
 }
 
 /// Errors encountered whilst working with contributions for a `TransactionModel`.
@@ -31,6 +56,41 @@ pub enum ContributionError {
     TooMuchRecursion,
 }
 
+impl<'a> PaymentAmounts<'a> {
+    pub fn new(value: Money, amounts: &'a BTreeMap<Date<Utc>, Decimal>) -> Self {
+        PaymentAmounts {
+            value,
+            amounts: Some(amounts),
+        }
+    }
+
+    pub fn get_amount(&self, date: Date<Utc>) -> Decimal {
+        self.amounts
+            .and_then(|a| a.get(&date))
+            .copied()
+            .unwrap_or_else(|| self.value.get_amount())
+    }
+
+    pub fn get_currency(&self) -> Currency {
+        self.value.get_currency()
+    }
+
+    // Sum the amounts of the payments on the given dates
+    fn get_total(&self, dates: &[Date<Utc>]) -> Money {
+        let total = dates.iter().map(|d| self.get_amount(*d)).sum();
+        Money::new(total, self.get_currency())
+    }
+}
+
+impl From<Money> for PaymentAmounts<'_> {
+    fn from(value: Money) -> Self {
+        PaymentAmounts {
+            value,
+            amounts: None,
+        }
+    }
+}
+
 impl Contribution {
     // Returns whether this `Contribution` has expired.
     // If `end_date` is not set, this `Contribution` will never expire.
@@ -41,27 +101,53 @@ impl Contribution {
     //     }
     // }
 
+    /// The amount to contribute on the given date, if this contribution covers it.
     #[allow(clippy::comparison_chain)]
-    pub fn regular_or_last(&self, date: Date<Utc>) -> Option<Decimal> {
+    pub fn regular_or_last(&self, date: Date<Utc>) -> Option<Money> {
         let period_end = self.get_period_end(Some(date));
-        if date >= self.start_date {
+        let amount = if date >= self.start_date {
             if date == period_end {
-                return self.last.or(Some(self.regular));
+                self.last.or(Some(self.regular))
             } else if date < period_end {
-                return Some(self.regular);
+                Some(self.regular)
+            } else {
+                None
             }
-        }
-        None
+        } else {
+            None
+        };
+        amount.map(|a| Money::new(self.round(date, a), self.currency))
     }
 
+    /// The amount to contribute on every day of a period, except the last, before
+    /// rounding.
+    pub fn get_regular(&self) -> Money {
+        Money::new(self.regular, self.currency)
+    }
+
+    /// The amount to contribute on the last day of a period, before rounding, if it
+    /// differs from the regular amount.
+    pub fn get_last(&self) -> Option<Money> {
+        self.last.map(|l| Money::new(l, self.currency))
+    }
+
+    /// The first day of this contribution.
     pub fn get_start_date(&self) -> Date<Utc> {
         self.start_date
     }
 
+    /// The last day of this contribution, or `None` if it repeats forever.
     pub fn get_end_date(&self) -> Option<Date<Utc>> {
         self.end_date
     }
 
+    /// The number of days in each period.
+    pub fn get_period_length(&self) -> Duration {
+        self.period_length
+    }
+
+    /// The last day of the period that contains `date`, or of the first period if no
+    /// date is given.
     pub fn get_period_end(&self, date: Option<Date<Utc>>) -> Date<Utc> {
         let date = date.unwrap_or(self.start_date);
         self.end_date.unwrap_or_else(|| {
@@ -80,7 +166,28 @@ impl Contribution {
         })
     }
 
-    pub fn set_end_date(&mut self, date: Date<Utc>, last_payment: Option<Date<Utc>>) {
+    // Round the `amount` contributed on `date` according to our rounding policy. Rather
+    // than rounding each day's amount in isolation, we round the running total for the
+    // period, so that any remainder is carried forward from one day to the next and the
+    // period's contributions still add up to its payments.
+    fn round(&self, date: Date<Utc>, amount: Decimal) -> Decimal {
+        if self.rounding == RoundingPolicy::Exact {
+            return amount;
+        }
+
+        // The number of days already contributed in this period
+        let days = (date - self.start_date).num_days() % self.period_length.num_days();
+        let running_total = |amount: Decimal| {
+            // Strip any representation error from an inexact regular amount (e.g. 1/3),
+            // so that totals which should land on a minor unit do.
+            let total = (self.regular * Decimal::from(days) + amount).round_dp(20);
+            self.rounding.round(total, self.currency)
+        };
+
+        running_total(amount) - running_total(Decimal::ZERO)
+    }
+
+    pub(crate) fn set_end_date(&mut self, date: Date<Utc>, last_payment: Option<Date<Utc>>) {
         // If the date is outside the range of this contribution, ignore it
         if date < self.start_date || (self.end_date.is_some() && Some(date) > self.end_date) {
             return;
@@ -121,7 +228,8 @@ impl Contribution {
 // first payment is tomorrow, I need an 'onboarding' amelioration to cover the payments
 // we haven't been saving for.
 pub(super) fn calculate(
-    value: Decimal,
+    amounts: PaymentAmounts,
+    rounding: RoundingPolicy,
     frequency: &Frequency,
     mut start_date: Date<Utc>,
     mut end_date: Option<Date<Utc>>,
@@ -140,13 +248,16 @@ pub(super) fn calculate(
     // for contributions. This approach doesn't work for repeating payments where there
     // is no end date; if our periods are not even, we will accumulate a surplus when we
     // actually need to break even.
-    if let Frequency::Once = frequency {
+    if frequency.is_once() {
+        // The payment may have been rolled to a business day, so use it rather than the
+        // start date.
+        let payment = payments.last().copied().unwrap_or(start_date);
         debug!(
             "adjusting date range to make use of lead time - start: {} => {}, end: {:?} => {}",
-            start_date, now, end_date, start_date
+            start_date, now, end_date, payment
         );
 
-        end_date = Some(start_date);
+        end_date = Some(payment);
         start_date = now;
     } else if end_date.is_some() {
         debug!(
@@ -173,8 +284,9 @@ pub(super) fn calculate(
         let payments_c = payments.clone();
 
         // Create a new Contribution from naive dates
-        let contribution =
-            naive_contribution(value, frequency, payments_c, start_date, end_date, None)?;
+        let contribution = naive_contribution(
+            amounts, rounding, frequency, payments_c, start_date, end_date, None,
+        )?;
 
         // Remove all payments covered by above contribution
         payments.retain(|p| *p < contribution.start_date);
@@ -211,7 +323,8 @@ pub(super) fn calculate(
 // that don't contribute to any payment, resulting in a shortfall on the last n
 // payments.
 fn naive_contribution(
-    payment: Decimal,
+    amounts: PaymentAmounts,
+    rounding: RoundingPolicy,
     frequency: &Frequency,
     mut payment_dates: Vec<Date<Utc>>,
     start_date: Date<Utc>,
@@ -288,14 +401,21 @@ fn naive_contribution(
         }
     }
 
-    // If every day in period has a payment, this contribution is valid
-    if payment_dates.len() as i64 == period_length.num_days() {
+    // If every day in period has a payment of the same amount, this contribution is valid
+    let first_amount = amounts.get_amount(payment_dates[0]);
+    if payment_dates.len() as i64 == period_length.num_days()
+        && payment_dates
+            .iter()
+            .all(|d| amounts.get_amount(*d) == first_amount)
+    {
         let c = Contribution {
-            regular: payment,
+            regular: first_amount,
             last: None,
+            currency: amounts.get_currency(),
             start_date,
             end_date,
             period_length,
+            rounding,
         };
 
         debug!(
@@ -308,7 +428,7 @@ fn naive_contribution(
 
     // Calculate contribution amounts
     let (regular, last) =
-        calculate_for_duration(payment, payment_dates.len() as u64, period_length)?;
+        calculate_for_duration(amounts.get_total(&payment_dates), period_length, rounding)?;
 
     debug!(
         "assuming the contribution has a regular value of ${}{}",
@@ -333,7 +453,8 @@ fn naive_contribution(
             );
 
             return naive_contribution(
-                payment,
+                amounts,
+                rounding,
                 frequency,
                 payment_dates,
                 start_date,
@@ -353,7 +474,8 @@ fn naive_contribution(
             );
 
             return naive_contribution(
-                payment,
+                amounts,
+                rounding,
                 frequency,
                 payment_dates,
                 first.succ(),
@@ -380,7 +502,8 @@ fn naive_contribution(
         }
 
         return naive_contribution(
-            payment,
+            amounts,
+            rounding,
             frequency,
             payment_dates,
             start_date.succ(),
@@ -391,8 +514,6 @@ fn naive_contribution(
 
     // Ensure that the duration between each payment is sufficient for accumulated
     // contributions to cover it.
-    // XXX Hack rounding to 23 decimal places - only solves rounding error for narrow test cases
-    let min_length = ((payment - last.unwrap_or(regular)) / regular + Decimal::ONE).round_dp(23);
     // Note we set lag_date to the day before start date, so that the duration
     // calculations will be correct. Every subsequent calculation is from payment date to
     // payment date, i.e. from the last day of the previous payment to the last day of
@@ -406,6 +527,12 @@ fn naive_contribution(
         let duration = *date - lag_date;
         let days = Decimal::from(duration.num_days());
 
+        // Calculate the number of days needed to accumulate this payment
+        // XXX Hack rounding to 23 decimal places - only solves rounding error for narrow test cases
+        let min_length = ((amounts.get_amount(*date) - last.unwrap_or(regular)) / regular
+            + Decimal::ONE)
+            .round_dp(23);
+
         // Calculate contribution length delta
         let delta = days - min_length;
 
@@ -438,8 +565,9 @@ fn naive_contribution(
         cumulative_delta += delta;
     }
 
-    // Handle a suggested new start date
-    if let Some(date) = first_positive_date {
+    // Handle a suggested new start date. When contributions are rounded up, every
+    // payment may be covered from the start date, which is not a new start date at all.
+    if let Some(date) = first_positive_date.filter(|d| *d >= start_date) {
         debug!(
             "the contribution does not cover all payments; choosing next viable start date: {}",
             date.succ()
@@ -455,7 +583,8 @@ fn naive_contribution(
         }
 
         return naive_contribution(
-            payment,
+            amounts,
+            rounding,
             frequency,
             payment_dates,
             date.succ(), // Shift forward 1 day as this date is for a payment
@@ -476,7 +605,8 @@ fn naive_contribution(
             );
 
             return naive_contribution(
-                payment,
+                amounts,
+                rounding,
                 frequency,
                 payment_dates,
                 first.succ(), // Shift forward 1 day as this date is for a payment
@@ -491,42 +621,44 @@ fn naive_contribution(
     let contribution = Contribution {
         regular,
         last,
+        currency: amounts.get_currency(),
         start_date,
         end_date,
         period_length,
+        rounding,
     };
     debug!("finalising contribution: {:?}", contribution);
 
     Ok(contribution)
 }
 
-// Calculate the contribution amounts for a value and duration
+// Calculate the contribution amounts for the total of a period's payments over a duration.
+// When rounding up, the regular amount is rounded here. Every other policy rounds the
+// running total of each day's contribution instead (see `Contribution::round`), so the
+// amounts returned here are exact.
 fn calculate_for_duration(
-    payment: Decimal,
-    num_payments: u64,
+    total: Money,
     duration: Duration,
+    rounding: RoundingPolicy,
 ) -> Result<(Decimal, Option<Decimal>), ContributionError> {
-    // Calculate total payments
-    let num_pay_dec = Decimal::from(num_payments);
-    let total = payment * num_pay_dec;
+    let currency = total.get_currency();
+    let total = total.get_amount();
 
     // Number of days in contribution
     let days = Decimal::from(duration.num_days());
 
-    trace!(
-        "calculating contribution for {} payments of {} (total={}) over {} days",
-        num_pay_dec,
-        payment,
-        total,
-        days
-    );
+    trace!("calculating contribution for {} over {} days", total, days);
 
     // Calculate the regular contribution
-    let regular = total / days;
+    let regular = match rounding {
+        RoundingPolicy::RoundUp => rounding.round(total / days, currency),
+        _ => total / days,
+    };
 
     // Given the potential rounding of the regular contribution, we may need a separate
-    // final contribution to handle the rounding error.
-    let last = if regular * days != total {
+    // final contribution to handle the rounding error. When rounding up, we keep
+    // contributing the regular amount and accrue the surplus instead.
+    let last = if regular * days != total && rounding != RoundingPolicy::RoundUp {
         Some(total - regular * (days - Decimal::ONE))
     } else {
         None
@@ -550,6 +682,7 @@ fn calculate_for_duration(
     // fundamentally broken!
     match last {
         Some(ref l) => assert_eq!(total, regular * (days - Decimal::ONE) + l),
+        None if rounding == RoundingPolicy::RoundUp => assert!(regular * days >= total),
         None => assert_eq!(total, regular * days),
     }
 
@@ -562,9 +695,32 @@ mod tests {
     use chrono::{Duration, TimeZone, Utc};
     use rust_decimal_macros::dec;
 
+    #[test]
+    fn contribution_accessors() {
+        let c = Contribution {
+            regular: dec!(0.33),
+            last: Some(dec!(0.34)),
+            currency: Currency::AUD,
+            start_date: Utc.ymd(2000, 4, 1),
+            end_date: None,
+            period_length: Duration::days(3),
+            rounding: RoundingPolicy::Exact,
+        };
+
+        assert_eq!(c.get_regular(), Money::new(dec!(0.33), Currency::AUD));
+        assert_eq!(c.get_last(), Some(Money::new(dec!(0.34), Currency::AUD)));
+        assert_eq!(c.get_period_length(), Duration::days(3));
+        assert_eq!(c.get_end_date(), None);
+    }
+
     #[test]
     fn calculate_for_duration_exact() {
-        let (regular, last) = calculate_for_duration(dec!(4.5), 2, Duration::days(4)).unwrap();
+        let (regular, last) = calculate_for_duration(
+            Money::new(dec!(9), Currency::AUD),
+            Duration::days(4),
+            RoundingPolicy::Exact,
+        )
+        .unwrap();
 
         assert_eq!(regular, dec!(2.25));
         assert_eq!(last, None);
@@ -572,12 +728,87 @@ mod tests {
 
     #[test]
     fn calculate_for_duration_rounding() {
-        let (regular, last) = calculate_for_duration(dec!(0.01), 1, Duration::days(365)).unwrap();
+        let (regular, last) = calculate_for_duration(
+            Money::new(dec!(0.01), Currency::AUD),
+            Duration::days(365),
+            RoundingPolicy::Exact,
+        )
+        .unwrap();
 
         assert_eq!(regular, dec!(0.000027397260273972602739726));
         assert_eq!(last, Some(dec!(0.000027397260273972602739736)));
     }
 
+    #[test]
+    fn calculate_for_duration_policies() {
+        let calc = |rounding, days| {
+            calculate_for_duration(
+                Money::new(dec!(1), Currency::AUD),
+                Duration::days(days),
+                rounding,
+            )
+        };
+
+        // Only rounding up changes the regular amount. Every other policy rounds the
+        // running total of each day's contribution.
+        let exact = calc(RoundingPolicy::Exact, 30);
+        assert_eq!(calc(RoundingPolicy::CarryForward, 30), exact);
+        assert_eq!(
+            calc(RoundingPolicy::Bankers, 60),
+            calc(RoundingPolicy::Exact, 60)
+        );
+        assert_eq!(calc(RoundingPolicy::RoundUp, 30), Ok((dec!(0.04), None)));
+        assert!(calc(RoundingPolicy::CarryForward, 365).is_ok());
+    }
+
+    #[test]
+    fn contribution_regular_or_last_rounding() {
+        let days = |rounding| {
+            let (regular, last) = calculate_for_duration(
+                Money::new(dec!(1), Currency::AUD),
+                Duration::days(3),
+                rounding,
+            )
+            .unwrap();
+            let c = Contribution {
+                regular,
+                last,
+                currency: Currency::AUD,
+                start_date: Utc.ymd(2000, 4, 1),
+                end_date: None,
+                period_length: Duration::days(3),
+                rounding,
+            };
+
+            (1..=6)
+                .map(|d| c.regular_or_last(Utc.ymd(2000, 4, d)).unwrap().get_amount())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            days(RoundingPolicy::CarryForward),
+            vec![
+                dec!(0.33),
+                dec!(0.33),
+                dec!(0.34),
+                dec!(0.33),
+                dec!(0.33),
+                dec!(0.34)
+            ]
+        );
+        assert_eq!(
+            days(RoundingPolicy::Bankers),
+            vec![
+                dec!(0.33),
+                dec!(0.34),
+                dec!(0.33),
+                dec!(0.33),
+                dec!(0.34),
+                dec!(0.33)
+            ]
+        );
+    }
+
     // #[test]
     // fn contribution_has_expired() {
     //     let c = Contribution {
@@ -616,11 +847,16 @@ mod tests {
         let c = Contribution {
             regular: dec!(1),
             last: None,
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: None,
             period_length: Duration::days(2),
+            rounding: RoundingPolicy::Exact,
         };
-        assert_eq!(c.regular_or_last(Utc.ymd(2000, 4, 2)), Some(dec!(1)));
+        assert_eq!(
+            c.regular_or_last(Utc.ymd(2000, 4, 2)),
+            Some(Money::new(dec!(1), Currency::AUD))
+        );
     }
 
     #[test]
@@ -628,11 +864,16 @@ mod tests {
         let c = Contribution {
             regular: dec!(1),
             last: Some(dec!(2)),
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: None,
             period_length: Duration::days(2),
+            rounding: RoundingPolicy::Exact,
         };
-        assert_eq!(c.regular_or_last(Utc.ymd(2000, 4, 4)), Some(dec!(2)));
+        assert_eq!(
+            c.regular_or_last(Utc.ymd(2000, 4, 4)),
+            Some(Money::new(dec!(2), Currency::AUD))
+        );
     }
 
     #[test]
@@ -640,9 +881,11 @@ mod tests {
         let c = Contribution {
             regular: dec!(1),
             last: None,
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: None,
             period_length: Duration::days(1),
+            rounding: RoundingPolicy::Exact,
         };
         assert!(c.regular_or_last(Utc.ymd(2000, 3, 31)).is_none());
     }
@@ -652,9 +895,11 @@ mod tests {
         let c = Contribution {
             regular: dec!(1),
             last: None,
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: Some(Utc.ymd(2000, 4, 3)),
             period_length: Duration::days(2),
+            rounding: RoundingPolicy::Exact,
         };
         assert!(c.regular_or_last(Utc.ymd(2000, 4, 4)).is_none());
     }
@@ -664,9 +909,11 @@ mod tests {
         let c = Contribution {
             regular: dec!(1),
             last: None,
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: None,
             period_length: Duration::days(2),
+            rounding: RoundingPolicy::Exact,
         };
         assert_eq!(c.get_period_end(None), Utc.ymd(2000, 4, 2));
     }
@@ -676,9 +923,11 @@ mod tests {
         let c = Contribution {
             regular: dec!(1),
             last: None,
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: Some(Utc.ymd(2000, 4, 2)),
             period_length: Duration::days(2),
+            rounding: RoundingPolicy::Exact,
         };
         assert_eq!(
             c.get_period_end(Some(Utc.ymd(2000, 4, 2))),
@@ -691,9 +940,11 @@ mod tests {
         let c = Contribution {
             regular: dec!(1),
             last: None,
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: None,
             period_length: Duration::days(2),
+            rounding: RoundingPolicy::Exact,
         };
         assert_eq!(
             c.get_period_end(Some(Utc.ymd(2000, 4, 7))),
@@ -706,9 +957,11 @@ mod tests {
         let mut c = Contribution {
             regular: dec!(1),
             last: Some(dec!(1.5)),
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: None,
             period_length: Duration::days(7),
+            rounding: RoundingPolicy::Exact,
         };
         let end = Utc.ymd(2000, 4, 9);
         c.set_end_date(end, Some(Utc.ymd(2000, 4, 7)));
@@ -722,9 +975,11 @@ mod tests {
         let mut c = Contribution {
             regular: dec!(1),
             last: Some(dec!(1.5)),
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: end,
             period_length: Duration::days(7),
+            rounding: RoundingPolicy::Exact,
         };
 
         // End date less than start date
@@ -747,9 +1002,11 @@ mod tests {
         let mut c = Contribution {
             regular: dec!(1),
             last: Some(dec!(1.5)),
+            currency: Currency::AUD,
             start_date: Utc.ymd(2000, 4, 1),
             end_date: None,
             period_length: Duration::days(7),
+            rounding: RoundingPolicy::Exact,
         };
         let end = Utc.ymd(2000, 4, 3);
         c.set_end_date(end, None);
@@ -762,8 +1019,15 @@ mod tests {
         let start = Utc.ymd(2000, 1, 2);
         let payment = Utc.ymd(2000, 1, 1);
         let payments = vec![payment];
-        let contribution =
-            naive_contribution(Decimal::ONE, &Frequency::Once, payments, start, None, None);
+        let contribution = naive_contribution(
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
+            &Frequency::Once,
+            payments,
+            start,
+            None,
+            None,
+        );
         assert_eq!(
             contribution.err(),
             Some(ContributionError::PaymentOutOfBounds(
@@ -781,7 +1045,8 @@ mod tests {
         let payment = Utc.ymd(2000, 1, 4);
         let payments = vec![payment];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Once,
             payments,
             start,
@@ -797,7 +1062,8 @@ mod tests {
     #[test]
     fn naive_contribution_empty_payments() {
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Once,
             Vec::new(),
             Utc.ymd(2000, 1, 2),
@@ -819,7 +1085,8 @@ mod tests {
             end,
         ];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Daily(1),
             payments,
             start,
@@ -831,9 +1098,11 @@ mod tests {
             Ok(Contribution {
                 regular: Decimal::ONE,
                 last: None,
+                currency: Currency::AUD,
                 start_date: start,
                 end_date: Some(end),
-                period_length: Duration::days(5)
+                period_length: Duration::days(5),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -844,7 +1113,8 @@ mod tests {
         let end = Utc.ymd(2000, 1, 3);
         let payments = vec![start, end];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Daily(2),
             payments,
             start,
@@ -856,9 +1126,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.5),
                 last: None,
+                currency: Currency::AUD,
                 start_date: start.succ(),
                 end_date: Some(end),
-                period_length: Duration::days(2)
+                period_length: Duration::days(2),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -868,7 +1140,8 @@ mod tests {
         let start = Utc.ymd(2000, 1, 1);
         let payments = vec![start];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Daily(2),
             payments,
             start,
@@ -880,9 +1153,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.5),
                 last: None,
+                currency: Currency::AUD,
                 start_date: start.succ(),
                 end_date: None,
-                period_length: Duration::days(2)
+                period_length: Duration::days(2),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -894,7 +1169,8 @@ mod tests {
         let pay_end = Utc.ymd(2000, 1, 2);
         let payments = vec![start, pay_end];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Daily(1),
             payments,
             start,
@@ -906,9 +1182,11 @@ mod tests {
             Ok(Contribution {
                 regular: Decimal::ONE,
                 last: None,
+                currency: Currency::AUD,
                 start_date: start,
                 end_date: Some(pay_end),
-                period_length: Duration::days(2)
+                period_length: Duration::days(2),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -922,7 +1200,8 @@ mod tests {
             Utc.ymd(2000, 1, 7),
         ];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![3, 4, 5]),
             payments,
             start,
@@ -934,9 +1213,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.4285714285714285714285714286),
                 last: Some(dec!(0.4285714285714285714285714284)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 1, 8),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -951,7 +1232,8 @@ mod tests {
             Utc.ymd(2000, 4, 7),
         ];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![1, 2, 4, 5]),
             payments,
             start,
@@ -963,9 +1245,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.5714285714285714285714285714),
                 last: Some(dec!(0.5714285714285714285714285716)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 8),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -979,7 +1263,8 @@ mod tests {
             Utc.ymd(2000, 4, 9),
         ];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![2, 4, 5, 7]),
             payments,
             Utc.ymd(2000, 4, 3),
@@ -991,9 +1276,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.5714285714285714285714285714),
                 last: Some(dec!(0.5714285714285714285714285716)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 8),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -1008,7 +1295,8 @@ mod tests {
             Utc.ymd(2000, 4, 9),
         ];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![1, 2, 3, 4, 7]),
             payments,
             Utc.ymd(2000, 4, 3),
@@ -1020,9 +1308,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.7142857142857142857142857143),
                 last: Some(dec!(0.7142857142857142857142857142)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 7),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -1031,7 +1321,8 @@ mod tests {
     fn naive_contribution_pattern4() {
         let payments = vec![Utc.ymd(2000, 4, 4), Utc.ymd(2000, 4, 8)];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![2, 6]),
             payments,
             Utc.ymd(2000, 4, 3),
@@ -1043,9 +1334,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.2857142857142857142857142857),
                 last: Some(dec!(0.2857142857142857142857142858)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 5),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -1054,7 +1347,8 @@ mod tests {
     fn naive_contribution_pattern5() {
         let payments = vec![Utc.ymd(2000, 4, 6), Utc.ymd(2000, 4, 9)];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![4, 6]),
             payments,
             Utc.ymd(2000, 4, 3),
@@ -1066,9 +1360,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.2857142857142857142857142857),
                 last: Some(dec!(0.2857142857142857142857142858)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 3),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -1077,7 +1373,8 @@ mod tests {
     fn naive_contribution_pattern6() {
         let payments = vec![Utc.ymd(2021, 7, 2)];
         let contribution = naive_contribution(
-            dec!(0.01),
+            Money::new(dec!(0.01), Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![5]),
             payments,
             Utc.ymd(2021, 7, 2),
@@ -1089,9 +1386,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.0014285714285714285714285714),
                 last: Some(dec!(0.0014285714285714285714285716)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2021, 7, 3),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -1100,7 +1399,8 @@ mod tests {
     fn naive_contribution_pattern7() {
         let payments = vec![Utc.ymd(2021, 7, 2)];
         let contribution = naive_contribution(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Weekly(1, vec![5]),
             payments,
             Utc.ymd(2021, 7, 2),
@@ -1112,9 +1412,11 @@ mod tests {
             Ok(Contribution {
                 regular: dec!(0.1428571428571428571428571429),
                 last: Some(dec!(0.1428571428571428571428571426)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2021, 7, 3),
                 end_date: None,
-                period_length: Duration::days(7)
+                period_length: Duration::days(7),
+                rounding: RoundingPolicy::Exact,
             })
         );
     }
@@ -1122,7 +1424,8 @@ mod tests {
     #[test]
     fn calculate_historical_error() {
         let result = calculate(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Once,
             Utc.ymd(2000, 4, 1),
             None,
@@ -1135,7 +1438,8 @@ mod tests {
     #[test]
     fn calculate_once() {
         let contributions = calculate(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Once,
             Utc.ymd(2000, 4, 2),
             None,
@@ -1146,9 +1450,11 @@ mod tests {
             Ok(vec![Contribution {
                 regular: dec!(0.5),
                 last: None,
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 1),
                 end_date: Some(Utc.ymd(2000, 4, 2)),
-                period_length: Duration::days(2)
+                period_length: Duration::days(2),
+                rounding: RoundingPolicy::Exact,
             }])
         );
     }
@@ -1156,7 +1462,8 @@ mod tests {
     #[test]
     fn calculate_daily_no_end() {
         let contributions = calculate(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Daily(2),
             Utc.ymd(2000, 4, 2),
             None,
@@ -1168,16 +1475,20 @@ mod tests {
                 Contribution {
                     regular: dec!(0.5),
                     last: None,
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 4, 1),
                     end_date: Some(Utc.ymd(2000, 4, 2)),
-                    period_length: Duration::days(2)
+                    period_length: Duration::days(2),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.5),
                     last: None,
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 4, 3),
                     end_date: None,
-                    period_length: Duration::days(2)
+                    period_length: Duration::days(2),
+                    rounding: RoundingPolicy::Exact,
                 }
             ])
         );
@@ -1186,7 +1497,8 @@ mod tests {
     #[test]
     fn calculate_daily_end_today() {
         let contributions = calculate(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Daily(2),
             Utc.ymd(2000, 4, 2),
             Some(Utc.ymd(2000, 4, 4)),
@@ -1198,16 +1510,20 @@ mod tests {
                 Contribution {
                     regular: Decimal::ONE,
                     last: None,
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 4, 2),
                     end_date: Some(Utc.ymd(2000, 4, 2)),
-                    period_length: Duration::days(1)
+                    period_length: Duration::days(1),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.5),
                     last: None,
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 4, 3),
                     end_date: Some(Utc.ymd(2000, 4, 4)),
-                    period_length: Duration::days(2)
+                    period_length: Duration::days(2),
+                    rounding: RoundingPolicy::Exact,
                 }
             ])
         );
@@ -1216,7 +1532,8 @@ mod tests {
     #[test]
     fn calculate_daily_end_yesterday() {
         let contributions = calculate(
-            Decimal::ONE,
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Daily(2),
             Utc.ymd(2000, 4, 2),
             Some(Utc.ymd(2000, 4, 4)),
@@ -1227,9 +1544,39 @@ mod tests {
             Ok(vec![Contribution {
                 regular: dec!(0.5),
                 last: None,
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 1),
                 end_date: Some(Utc.ymd(2000, 4, 4)),
-                period_length: Duration::days(4)
+                period_length: Duration::days(4),
+                rounding: RoundingPolicy::Exact,
+            }])
+        );
+    }
+
+    #[test]
+    fn calculate_once_adjusted() {
+        let contributions = calculate(
+            Money::new(Decimal::ONE, Currency::AUD).into(),
+            RoundingPolicy::Exact,
+            &Frequency::Adjusted(
+                Box::new(Frequency::Once),
+                crate::BusinessDayConvention::Following,
+                crate::HolidayCalendar::default(),
+            ),
+            Utc.ymd(2000, 4, 1),
+            None,
+            Utc.ymd(2000, 3, 31),
+        );
+        assert_eq!(
+            contributions,
+            Ok(vec![Contribution {
+                regular: dec!(0.25),
+                last: None,
+                currency: Currency::AUD,
+                start_date: Utc.ymd(2000, 3, 31),
+                end_date: Some(Utc.ymd(2000, 4, 3)),
+                period_length: Duration::days(4),
+                rounding: RoundingPolicy::Exact,
             }])
         );
     }
@@ -1237,7 +1584,8 @@ mod tests {
     #[test]
     fn calculate_approaching_zero() {
         let contributions = calculate(
-            dec!(0.01),
+            Money::new(dec!(0.01), Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Once,
             Utc.ymd(2000, 4, 3),
             None,
@@ -1248,9 +1596,11 @@ mod tests {
             Ok(vec![Contribution {
                 regular: dec!(0.0033333333333333333333333333),
                 last: Some(dec!(0.0033333333333333333333333334)),
+                currency: Currency::AUD,
                 start_date: Utc.ymd(2000, 4, 1),
                 end_date: Some(Utc.ymd(2000, 4, 3)),
-                period_length: Duration::days(3)
+                period_length: Duration::days(3),
+                rounding: RoundingPolicy::Exact,
             }])
         );
     }
@@ -1258,7 +1608,8 @@ mod tests {
     #[test]
     fn calculate_small_payment_biannually() {
         let contributions = calculate(
-            dec!(5.0),
+            Money::new(dec!(5.0), Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Yearly(1, vec![2, 8], None, None),
             Utc.ymd(2000, 1, 1),
             None,
@@ -1270,30 +1621,38 @@ mod tests {
                 Contribution {
                     regular: dec!(0.15625),
                     last: None,
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 01, 01),
                     end_date: Some(Utc.ymd(2000, 02, 01)),
-                    period_length: Duration::days(32)
+                    period_length: Duration::days(32),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.0274725274725274725274725275),
                     last: Some(dec!(0.0274725274725274725274725225)),
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 02, 02),
                     end_date: Some(Utc.ymd(2000, 08, 01)),
-                    period_length: Duration::days(182)
+                    period_length: Duration::days(182),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.0273972602739726027397260274),
                     last: Some(dec!(0.027397260273972602739726024)),
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 08, 02),
                     end_date: Some(Utc.ymd(2003, 08, 01)),
-                    period_length: Duration::days(1095)
+                    period_length: Duration::days(1095),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.0273785078713210130047912389),
                     last: Some(dec!(0.027378507871321013004791206)),
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2003, 08, 02),
                     end_date: None,
-                    period_length: Duration::days(1461)
+                    period_length: Duration::days(1461),
+                    rounding: RoundingPolicy::Exact,
                 }
             ])
         );
@@ -1302,7 +1661,8 @@ mod tests {
     #[test]
     fn calculate_small_payment_biennially() {
         let contributions = calculate(
-            dec!(5.0),
+            Money::new(dec!(5.0), Currency::AUD).into(),
+            RoundingPolicy::Exact,
             &Frequency::Yearly(2, vec![2, 8], None, None),
             Utc.ymd(2000, 1, 1),
             None,
@@ -1314,30 +1674,38 @@ mod tests {
                 Contribution {
                     regular: dec!(0.15625),
                     last: None,
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 01, 01),
                     end_date: Some(Utc.ymd(2000, 02, 01)),
-                    period_length: Duration::days(32)
+                    period_length: Duration::days(32),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.0274725274725274725274725275),
                     last: Some(dec!(0.0274725274725274725274725225)),
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 02, 02),
                     end_date: Some(Utc.ymd(2000, 08, 01)),
-                    period_length: Duration::days(182)
+                    period_length: Duration::days(182),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.0136986301369863013698630137),
                     last: Some(dec!(0.013698630136986301369863013)),
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2000, 08, 02),
                     end_date: Some(Utc.ymd(2002, 08, 01)),
-                    period_length: Duration::days(730)
+                    period_length: Duration::days(730),
+                    rounding: RoundingPolicy::Exact,
                 },
                 Contribution {
                     regular: dec!(0.0136892539356605065023956194),
                     last: Some(dec!(0.013689253935660506502395676)),
+                    currency: Currency::AUD,
                     start_date: Utc.ymd(2002, 08, 02),
                     end_date: None,
-                    period_length: Duration::days(1461)
+                    period_length: Duration::days(1461),
+                    rounding: RoundingPolicy::Exact,
                 }
             ])
         );
@@ -0,0 +1,199 @@
+use std::{
+    convert::TryFrom,
+    fmt::{Display, Error, Formatter},
+    str::FromStr,
+};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// The active ISO 4217 currency codes and the number of digits in their minor unit,
+// sorted by code so that we can binary search them.
+#[rustfmt::skip]
+const ISO_4217: [(&str, u32); 168] = [
+    ("AED", 2), ("AFN", 2), ("ALL", 2), ("AMD", 2), ("ANG", 2), ("AOA", 2),
+    ("ARS", 2), ("AUD", 2), ("AWG", 2), ("AZN", 2), ("BAM", 2), ("BBD", 2),
+    ("BDT", 2), ("BGN", 2), ("BHD", 3), ("BIF", 0), ("BMD", 2), ("BND", 2),
+    ("BOB", 2), ("BOV", 2), ("BRL", 2), ("BSD", 2), ("BTN", 2), ("BWP", 2),
+    ("BYN", 2), ("BZD", 2), ("CAD", 2), ("CDF", 2), ("CHE", 2), ("CHF", 2),
+    ("CHW", 2), ("CLF", 4), ("CLP", 0), ("CNY", 2), ("COP", 2), ("COU", 2),
+    ("CRC", 2), ("CUC", 2), ("CUP", 2), ("CVE", 2), ("CZK", 2), ("DJF", 0),
+    ("DKK", 2), ("DOP", 2), ("DZD", 2), ("EGP", 2), ("ERN", 2), ("ETB", 2),
+    ("EUR", 2), ("FJD", 2), ("FKP", 2), ("GBP", 2), ("GEL", 2), ("GHS", 2),
+    ("GIP", 2), ("GMD", 2), ("GNF", 0), ("GTQ", 2), ("GYD", 2), ("HKD", 2),
+    ("HNL", 2), ("HTG", 2), ("HUF", 2), ("IDR", 2), ("ILS", 2), ("INR", 2),
+    ("IQD", 3), ("IRR", 2), ("ISK", 0), ("JMD", 2), ("JOD", 3), ("JPY", 0),
+    ("KES", 2), ("KGS", 2), ("KHR", 2), ("KMF", 0), ("KPW", 2), ("KRW", 0),
+    ("KWD", 3), ("KYD", 2), ("KZT", 2), ("LAK", 2), ("LBP", 2), ("LKR", 2),
+    ("LRD", 2), ("LSL", 2), ("LYD", 3), ("MAD", 2), ("MDL", 2), ("MGA", 2),
+    ("MKD", 2), ("MMK", 2), ("MNT", 2), ("MOP", 2), ("MRU", 2), ("MUR", 2),
+    ("MVR", 2), ("MWK", 2), ("MXN", 2), ("MXV", 2), ("MYR", 2), ("MZN", 2),
+    ("NAD", 2), ("NGN", 2), ("NIO", 2), ("NOK", 2), ("NPR", 2), ("NZD", 2),
+    ("OMR", 3), ("PAB", 2), ("PEN", 2), ("PGK", 2), ("PHP", 2), ("PKR", 2),
+    ("PLN", 2), ("PYG", 0), ("QAR", 2), ("RON", 2), ("RSD", 2), ("RUB", 2),
+    ("RWF", 0), ("SAR", 2), ("SBD", 2), ("SCR", 2), ("SDG", 2), ("SEK", 2),
+    ("SGD", 2), ("SHP", 2), ("SLE", 2), ("SLL", 2), ("SOS", 2), ("SRD", 2),
+    ("SSP", 2), ("STN", 2), ("SVC", 2), ("SYP", 2), ("SZL", 2), ("THB", 2),
+    ("TJS", 2), ("TMT", 2), ("TND", 3), ("TOP", 2), ("TRY", 2), ("TTD", 2),
+    ("TWD", 2), ("TZS", 2), ("UAH", 2), ("UGX", 0), ("USD", 2), ("USN", 2),
+    ("UYI", 0), ("UYU", 2), ("UYW", 4), ("UZS", 2), ("VED", 2), ("VES", 2),
+    ("VND", 0), ("VUV", 0), ("WST", 2), ("XAF", 0), ("XCD", 2), ("XOF", 0),
+    ("XPF", 0), ("YER", 2), ("ZAR", 2), ("ZMW", 2), ("ZWG", 2), ("ZWL", 2),
+];
+
+/// An ISO 4217 currency.
+///
+/// The currency determines how many decimal places an amount can validly express. For
+/// example, Australian dollars have 2 decimal places, whereas Japanese yen have none and
+/// Kuwaiti dinars have 3.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Currency {
+    code: [u8; 3],
+    minor_units: u32,
+}
+
+/// Errors encountered whilst working with [`Currency`]s.
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum CurrencyError {
+    #[error("'{0}' is not an ISO 4217 currency code")]
+    UnknownCode(String),
+    #[error("cannot combine amounts in {0} and {1}")]
+    Mismatch(Currency, Currency),
+}
+
+impl Currency {
+    pub const AUD: Currency = Currency::from_table(*b"AUD", 2);
+    pub const CAD: Currency = Currency::from_table(*b"CAD", 2);
+    pub const EUR: Currency = Currency::from_table(*b"EUR", 2);
+    pub const GBP: Currency = Currency::from_table(*b"GBP", 2);
+    pub const JPY: Currency = Currency::from_table(*b"JPY", 0);
+    pub const NZD: Currency = Currency::from_table(*b"NZD", 2);
+    pub const USD: Currency = Currency::from_table(*b"USD", 2);
+
+    /// Find a currency by its ISO 4217 code, e.g. "AUD".
+    pub fn new(code: &str) -> Result<Self, CurrencyError> {
+        ISO_4217
+            .binary_search_by_key(&code, |(c, _)| c)
+            .map(|i| {
+                let mut bytes = [0; 3];
+                bytes.copy_from_slice(ISO_4217[i].0.as_bytes());
+                Currency::from_table(bytes, ISO_4217[i].1)
+            })
+            .map_err(|_| CurrencyError::UnknownCode(code.into()))
+    }
+
+    /// The ISO 4217 currency code, e.g. "AUD".
+    pub fn get_code(&self) -> &str {
+        // Codes only ever come from the ISO 4217 table, which is ASCII
+        std::str::from_utf8(&self.code).unwrap()
+    }
+
+    /// The number of decimal places in this currency's minor unit.
+    pub fn get_minor_units(&self) -> u32 {
+        self.minor_units
+    }
+
+    /// The smallest amount this currency can express, e.g. 0.01 for Australian dollars.
+    pub fn get_minor_unit(&self) -> Decimal {
+        Decimal::new(1, self.minor_units)
+    }
+
+    /// Whether the given amount can be expressed in this currency's minor unit.
+    pub fn is_valid_amount(&self, amount: Decimal) -> bool {
+        self.round(amount) == amount
+    }
+
+    /// Round the given amount to this currency's minor unit, using banker's rounding.
+    pub fn round(&self, amount: Decimal) -> Decimal {
+        amount.round_dp(self.minor_units)
+    }
+
+    const fn from_table(code: [u8; 3], minor_units: u32) -> Self {
+        Currency { code, minor_units }
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.get_code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = CurrencyError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Currency::new(code)
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = CurrencyError;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Currency::new(&code)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.get_code().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn iso_4217_sorted() {
+        assert!(ISO_4217.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn new_currency() {
+        assert_eq!(Currency::new("AUD"), Ok(Currency::AUD));
+        assert_eq!(Currency::new("JPY"), Ok(Currency::JPY));
+        assert_eq!(Currency::new("KWD").unwrap().get_minor_units(), 3);
+        assert_eq!(
+            "aud".parse::<Currency>(),
+            Err(CurrencyError::UnknownCode("aud".into()))
+        );
+    }
+
+    #[test]
+    fn constants_match_table() {
+        for currency in [
+            Currency::AUD,
+            Currency::CAD,
+            Currency::EUR,
+            Currency::GBP,
+            Currency::JPY,
+            Currency::NZD,
+            Currency::USD,
+        ] {
+            assert_eq!(Currency::new(currency.get_code()), Ok(currency));
+        }
+    }
+
+    #[test]
+    fn valid_amounts() {
+        let kwd = Currency::new("KWD").unwrap();
+
+        assert!(Currency::AUD.is_valid_amount(dec!(1.25)));
+        assert!(!Currency::AUD.is_valid_amount(dec!(1.255)));
+        assert!(Currency::JPY.is_valid_amount(dec!(100)));
+        assert!(!Currency::JPY.is_valid_amount(dec!(100.5)));
+        assert!(kwd.is_valid_amount(dec!(1.255)));
+        assert_eq!(kwd.get_minor_unit(), dec!(0.001));
+        assert_eq!(Currency::JPY.round(dec!(100.5)), dec!(100));
+    }
+}
@@ -0,0 +1,279 @@
+use std::{collections::BTreeMap, fs, io, path::Path, str::FromStr};
+
+use crate::money::Money;
+use chrono::{Date, Datelike, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A rule for increasing the value of a recurring `TransactionModel` over time, such as
+/// an annual rent increase or a salary indexed to inflation.
+///
+/// Escalated values are rounded to the currency's minor unit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Escalation {
+    /// Increase the value by a percentage (e.g. 3 for 3%) on the given anniversary date,
+    /// and on the same date every year after. Anniversaries on or before the model's
+    /// start date are assumed to be included in the model's value already.
+    Annual(
+        Decimal,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date"))] Date<Utc>,
+    ),
+    /// Change the value to the given amount from each date
+    Steps(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date_map"))]
+        BTreeMap<Date<Utc>, Decimal>,
+    ),
+    /// Scale the value in line with an index (e.g. CPI), relative to the index at the
+    /// model's start date
+    Index(IndexTable),
+}
+
+/// Errors encountered whilst loading an [`IndexTable`].
+#[derive(Error, Debug)]
+pub enum IndexTableError {
+    #[error("could not read index file")]
+    Io(#[from] io::Error),
+    #[error("invalid index on line {0}: {1}")]
+    InvalidEntry(usize, String),
+}
+
+/// A table of index values (e.g. CPI), keyed by date.
+///
+/// An index value applies from its date until the next value in the table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IndexTable {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date_map"))]
+    values: BTreeMap<Date<Utc>, Decimal>,
+}
+
+impl Escalation {
+    // Get the escalated `value` of a payment on `date`, for a model that starts on
+    // `start`
+    pub(crate) fn escalate(&self, value: Money, start: Date<Utc>, date: Date<Utc>) -> Money {
+        let currency = value.get_currency();
+        let amount = match self {
+            Escalation::Annual(rate, anniversary) => {
+                let factor = Decimal::ONE + rate / Decimal::ONE_HUNDRED;
+                (0..)
+                    .map_while(|n| add_years(*anniversary, n))
+                    .take_while(|d| *d <= date)
+                    .filter(|d| *d > start)
+                    .fold(value.get_amount(), |amount, _| {
+                        currency.round(amount * factor)
+                    })
+            }
+            Escalation::Steps(steps) => steps
+                .range(..=date)
+                .next_back()
+                .map(|(_, amount)| *amount)
+                .unwrap_or_else(|| value.get_amount()),
+            Escalation::Index(table) => match (table.get_value(start), table.get_value(date)) {
+                (Some(base), Some(index)) => currency.round(value.get_amount() * index / base),
+                _ => value.get_amount(),
+            },
+        };
+
+        Money::new(amount, currency)
+    }
+
+    // Get the dates that the value changes after `start`, up to and including `until`
+    pub(crate) fn get_changes(&self, start: Date<Utc>, until: Date<Utc>) -> Vec<Date<Utc>> {
+        let dates: Vec<Date<Utc>> = match self {
+            Escalation::Annual(_, anniversary) => (0..)
+                .map_while(|n| add_years(*anniversary, n))
+                .take_while(|d| *d <= until)
+                .collect(),
+            Escalation::Steps(steps) => steps.keys().copied().collect(),
+            Escalation::Index(table) => table.values.keys().copied().collect(),
+        };
+
+        dates
+            .into_iter()
+            .filter(|d| *d > start && *d <= until)
+            .collect()
+    }
+
+    // Get the date of the last change in value, or `None` if the value changes forever
+    pub(crate) fn get_last_change(&self) -> Option<Date<Utc>> {
+        match self {
+            Escalation::Annual(..) => None,
+            Escalation::Steps(steps) => steps.keys().next_back().copied(),
+            Escalation::Index(table) => table.values.keys().next_back().copied(),
+        }
+    }
+}
+
+impl IndexTable {
+    pub fn new() -> Self {
+        IndexTable::default()
+    }
+
+    /// Parse a table of index values from CSV, with one value per line:
+    ///
+    /// ```text
+    /// date,index
+    /// 2000-01-01,68.1
+    /// 2000-04-01,68.7
+    /// ```
+    ///
+    /// The header line is optional. Blank lines and comments (starting with `#`) are
+    /// ignored.
+    pub fn parse(csv: &str) -> Result<Self, IndexTableError> {
+        let mut table = IndexTable::new();
+
+        for (i, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("date,") {
+                continue;
+            }
+
+            let invalid = |reason: String| IndexTableError::InvalidEntry(i + 1, reason);
+
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            let (date, value) = match fields[..] {
+                [date, value] => (date, value),
+                _ => return Err(invalid("expected 'date,index'".into())),
+            };
+
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(|d| Date::from_utc(d, Utc))
+                .map_err(|e| invalid(e.to_string()))?;
+            let value = Decimal::from_str(value)
+                .ok()
+                .filter(|v| *v > Decimal::ZERO)
+                .ok_or_else(|| invalid(format!("'{}' is not a valid index", value)))?;
+
+            table.insert(date, value);
+        }
+
+        Ok(table)
+    }
+
+    /// Load a table of index values from a CSV file. See [`IndexTable::parse`] for the
+    /// format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, IndexTableError> {
+        IndexTable::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Set the index value, effective from the given date.
+    pub fn insert(&mut self, date: Date<Utc>, value: Decimal) {
+        self.values.insert(date, value);
+    }
+
+    /// Get the index value on the given date, which is the latest value on or before
+    /// that date.
+    pub fn get_value(&self, date: Date<Utc>) -> Option<Decimal> {
+        self.values.range(..=date).next_back().map(|(_, v)| *v)
+    }
+}
+
+// Add a number of years to a date, moving 29 February to 28 February in non-leap years
+pub(crate) fn add_years(date: Date<Utc>, years: i32) -> Option<Date<Utc>> {
+    let year = date.year().checked_add(years)?;
+    Utc.ymd_opt(year, date.month(), date.day())
+        .single()
+        .or_else(|| Utc.ymd_opt(year, date.month(), date.day() - 1).single())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn escalate_annual() {
+        let start = Utc.ymd(2000, 4, 1);
+        let escalation = Escalation::Annual(dec!(3), Utc.ymd(2000, 7, 1));
+        let escalate = |date| escalation.escalate(Money::aud(dec!(1000)), start, date);
+
+        assert_eq!(escalate(Utc.ymd(2000, 6, 30)), Money::aud(dec!(1000)));
+        assert_eq!(escalate(Utc.ymd(2000, 7, 1)), Money::aud(dec!(1030)));
+        assert_eq!(escalate(Utc.ymd(2002, 7, 1)), Money::aud(dec!(1092.73)));
+        assert_eq!(
+            escalation.get_changes(start, Utc.ymd(2002, 7, 1)),
+            vec![
+                Utc.ymd(2000, 7, 1),
+                Utc.ymd(2001, 7, 1),
+                Utc.ymd(2002, 7, 1)
+            ]
+        );
+
+        // Past anniversaries are already included in the value
+        let escalation = Escalation::Annual(dec!(3), Utc.ymd(1998, 4, 1));
+        assert_eq!(
+            escalation.escalate(Money::aud(dec!(1000)), start, Utc.ymd(2000, 12, 1)),
+            Money::aud(dec!(1000))
+        );
+    }
+
+    #[test]
+    fn escalate_steps() {
+        let escalation = Escalation::Steps(
+            vec![
+                (Utc.ymd(2000, 7, 1), dec!(1100)),
+                (Utc.ymd(2001, 7, 1), dec!(1150)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let escalate =
+            |date| escalation.escalate(Money::aud(dec!(1000)), Utc.ymd(2000, 4, 1), date);
+
+        assert_eq!(escalate(Utc.ymd(2000, 6, 30)), Money::aud(dec!(1000)));
+        assert_eq!(escalate(Utc.ymd(2001, 1, 1)), Money::aud(dec!(1100)));
+        assert_eq!(escalate(Utc.ymd(2005, 1, 1)), Money::aud(dec!(1150)));
+        assert_eq!(escalation.get_last_change(), Some(Utc.ymd(2001, 7, 1)));
+        assert_eq!(
+            escalation.get_changes(Utc.ymd(2000, 7, 1), Utc.ymd(2005, 1, 1)),
+            vec![Utc.ymd(2001, 7, 1)]
+        );
+    }
+
+    #[test]
+    fn escalate_index() {
+        let table = IndexTable::parse(
+            "
+date,index
+2000-01-01,100
+# Quarterly CPI
+2000-04-01,102
+2000-07-01,103.5
+",
+        )
+        .unwrap();
+        let escalation = Escalation::Index(table);
+        let escalate =
+            |date| escalation.escalate(Money::aud(dec!(1000)), Utc.ymd(2000, 4, 1), date);
+
+        assert_eq!(escalate(Utc.ymd(2000, 5, 1)), Money::aud(dec!(1000)));
+        assert_eq!(escalate(Utc.ymd(2000, 8, 1)), Money::aud(dec!(1014.71)));
+    }
+
+    #[test]
+    fn parse_index_invalid() {
+        assert!(matches!(
+            IndexTable::parse("2000-01-01"),
+            Err(IndexTableError::InvalidEntry(1, _))
+        ));
+        assert!(matches!(
+            IndexTable::parse("\n2000-01-01,0"),
+            Err(IndexTableError::InvalidEntry(2, _))
+        ));
+    }
+
+    #[test]
+    fn add_years_leap_day() {
+        assert_eq!(
+            add_years(Utc.ymd(2000, 2, 29), 1),
+            Some(Utc.ymd(2001, 2, 28))
+        );
+        assert_eq!(
+            add_years(Utc.ymd(2000, 2, 29), 4),
+            Some(Utc.ymd(2004, 2, 29))
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::{collections::BTreeMap, fs, io, path::Path, str::FromStr};
+
+use crate::{currency::Currency, money::Money};
+use chrono::{Date, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+/// A source of exchange rates between [`Currency`]s.
+pub trait ExchangeRates {
+    /// The number of units of `to` that one unit of `from` buys on the given date.
+    fn get_rate(&self, from: Currency, to: Currency, date: Date<Utc>) -> Option<Decimal>;
+
+    /// Convert an amount of money into another currency at the rate for the given date.
+    fn convert(&self, money: Money, to: Currency, date: Date<Utc>) -> Result<Money, ExchangeError> {
+        if money.get_currency() == to {
+            return Ok(money);
+        }
+
+        self.get_rate(money.get_currency(), to, date)
+            .map(|rate| Money::new(money.get_amount() * rate, to))
+            .ok_or(ExchangeError::MissingRate(money.get_currency(), to, date))
+    }
+}
+
+/// Errors encountered whilst converting between currencies.
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum ExchangeError {
+    #[error("there is no exchange rate from {0} to {1} on {2}")]
+    MissingRate(Currency, Currency, Date<Utc>),
+}
+
+/// Errors encountered whilst loading a [`RateTable`].
+#[derive(Error, Debug)]
+pub enum RateTableError {
+    #[error("could not read exchange rate file")]
+    Io(#[from] io::Error),
+    #[error("invalid exchange rate on line {0}: {1}")]
+    InvalidEntry(usize, String),
+}
+
+/// An in-memory table of exchange rates, keyed by date.
+///
+/// A rate applies from its date until the next rate for the same currency pair, so a
+/// table only needs an entry when the rate changes. If a table has a rate for one
+/// direction only (e.g. USD to AUD), the inverse rate is used for the other direction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateTable {
+    rates: BTreeMap<(Currency, Currency), BTreeMap<Date<Utc>, Decimal>>,
+}
+
+impl RateTable {
+    pub fn new() -> Self {
+        RateTable::default()
+    }
+
+    /// Parse a table of rates from CSV, with one rate per line:
+    ///
+    /// ```text
+    /// date,from,to,rate
+    /// 2000-04-01,USD,AUD,1.6580
+    /// 2000-05-01,USD,AUD,1.7112
+    /// ```
+    ///
+    /// The header line is optional. Blank lines and comments (starting with `#`) are
+    /// ignored.
+    pub fn parse(csv: &str) -> Result<Self, RateTableError> {
+        let mut table = RateTable::new();
+
+        for (i, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("date,") {
+                continue;
+            }
+
+            let invalid = |reason: String| RateTableError::InvalidEntry(i + 1, reason);
+
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            let (date, from, to, rate) = match fields[..] {
+                [date, from, to, rate] => (date, from, to, rate),
+                _ => return Err(invalid("expected 'date,from,to,rate'".into())),
+            };
+
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(|d| Date::from_utc(d, Utc))
+                .map_err(|e| invalid(e.to_string()))?;
+            let from = Currency::new(from).map_err(|e| invalid(e.to_string()))?;
+            let to = Currency::new(to).map_err(|e| invalid(e.to_string()))?;
+            let rate = Decimal::from_str(rate)
+                .ok()
+                .filter(|r| *r > Decimal::ZERO)
+                .ok_or_else(|| invalid(format!("'{}' is not a valid rate", rate)))?;
+
+            table.insert(from, to, date, rate);
+        }
+
+        Ok(table)
+    }
+
+    /// Load a table of rates from a CSV file. See [`RateTable::parse`] for the format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RateTableError> {
+        RateTable::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Set the rate from one currency to another, effective from the given date.
+    pub fn insert(&mut self, from: Currency, to: Currency, date: Date<Utc>, rate: Decimal) {
+        self.rates.entry((from, to)).or_default().insert(date, rate);
+    }
+
+    // Get the latest rate on or before the date for this exact currency pair
+    fn get_direct_rate(&self, from: Currency, to: Currency, date: Date<Utc>) -> Option<Decimal> {
+        self.rates
+            .get(&(from, to))?
+            .range(..=date)
+            .next_back()
+            .map(|(_, rate)| *rate)
+    }
+}
+
+impl ExchangeRates for RateTable {
+    fn get_rate(&self, from: Currency, to: Currency, date: Date<Utc>) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+
+        self.get_direct_rate(from, to, date).or_else(|| {
+            self.get_direct_rate(to, from, date)
+                .map(|rate| Decimal::ONE / rate)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    const RATES: &str = "
+date,from,to,rate
+2000-04-01,USD,AUD,1.6
+# The rate changes mid-month
+2000-04-15,USD,AUD,1.5
+";
+
+    #[test]
+    fn get_rate_by_date() {
+        let table = RateTable::parse(RATES).unwrap();
+
+        assert_eq!(
+            table.get_rate(Currency::USD, Currency::AUD, Utc.ymd(2000, 3, 31)),
+            None
+        );
+        assert_eq!(
+            table.get_rate(Currency::USD, Currency::AUD, Utc.ymd(2000, 4, 14)),
+            Some(dec!(1.6))
+        );
+        assert_eq!(
+            table.get_rate(Currency::USD, Currency::AUD, Utc.ymd(2000, 6, 1)),
+            Some(dec!(1.5))
+        );
+        assert_eq!(
+            table.get_rate(Currency::AUD, Currency::USD, Utc.ymd(2000, 4, 1)),
+            Some(dec!(0.625))
+        );
+        assert_eq!(
+            table.get_rate(Currency::JPY, Currency::JPY, Utc.ymd(2000, 4, 1)),
+            Some(Decimal::ONE)
+        );
+    }
+
+    #[test]
+    fn convert_money() {
+        let table = RateTable::parse(RATES).unwrap();
+        let date = Utc.ymd(2000, 4, 1);
+
+        assert_eq!(
+            table.convert(Money::new(dec!(10), Currency::USD), Currency::AUD, date),
+            Ok(Money::new(dec!(16), Currency::AUD))
+        );
+        assert_eq!(
+            table.convert(Money::new(dec!(10), Currency::USD), Currency::EUR, date),
+            Err(ExchangeError::MissingRate(
+                Currency::USD,
+                Currency::EUR,
+                date
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(matches!(
+            RateTable::parse("2000-04-01,USD,AUD"),
+            Err(RateTableError::InvalidEntry(1, _))
+        ));
+        assert!(matches!(
+            RateTable::parse("\n2000-04-01,USD,XYZ,1.5"),
+            Err(RateTableError::InvalidEntry(2, _))
+        ));
+        assert!(matches!(
+            RateTable::parse("2000-04-01,USD,AUD,-1"),
+            Err(RateTableError::InvalidEntry(1, _))
+        ));
+    }
+}
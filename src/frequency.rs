@@ -1,6 +1,9 @@
 use std::fmt::{Display, Error, Formatter};
 
+use crate::{business_day::BusinessDayConvention, holiday::HolidayCalendar};
 use chrono::{Date, Datelike, Duration, LocalResult, TimeZone, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 // This constant represents the shortest number of days that is guaranteed to be
 // consistent. It is used to smooth periods that span months or years. Both units contain
@@ -13,7 +16,8 @@ const MONTH_LENGTHS: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31
 const MONTH_LENGTHS_LEAP: [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 
 /// The frequency of a `TransactionModel`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Frequency {
     /// A single transaction
     Once,
@@ -37,10 +41,15 @@ pub enum Frequency {
     ///
     /// _`nth` = 1 (first), 2 (second), ..., 5 (fifth), 0 (last)_
     Yearly(u32, Vec<u32>, Option<u32>, Option<FrequencyMonthDay>),
+    /// Transactions for the inner `Frequency`, with payment dates that don't fall on a
+    /// business day rolled according to the `BusinessDayConvention`. Holidays in the
+    /// `HolidayCalendar` are not business days.
+    Adjusted(Box<Frequency>, BusinessDayConvention, HolidayCalendar),
 }
 
 /// The days that a monthly or yearly `TransactionModel` repeats on.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FrequencyMonthDay {
     Monday,
     Tuesday,
@@ -73,6 +82,58 @@ impl Frequency {
                 // leap years.
                 to_macro_periods(years as f32 * 365.25)
             }
+            Frequency::Adjusted(ref frequency, _, _) => frequency.get_period_length(),
+        }
+    }
+
+    // Whether this frequency represents a single transaction
+    pub(crate) fn is_once(&self) -> bool {
+        match *self {
+            Frequency::Once => true,
+            Frequency::Adjusted(ref frequency, _, _) => frequency.is_once(),
+            _ => false,
+        }
+    }
+
+    /// Lazily iterate over every payment date from `start`, in date order.
+    ///
+    /// Unlike the dates for a single period, this iterator continues indefinitely for
+    /// repeating frequencies, so use it with an adapter such as `take_while`. A
+    /// `Frequency` with no possible dates (e.g. 30 February) yields nothing.
+    pub fn occurrences(&self, start: Date<Utc>) -> impl Iterator<Item = Date<Utc>> + '_ {
+        Occurrences::new(self, start)
+    }
+
+    /// Lazily iterate over every payment date from `start` to `end` (inclusive), in date
+    /// order.
+    pub fn occurrences_between(
+        &self,
+        start: Date<Utc>,
+        end: Date<Utc>,
+    ) -> impl Iterator<Item = Date<Utc>> + '_ {
+        self.occurrences(start).take_while(move |d| *d <= end)
+    }
+
+    // Get the date of the `n`th payment from `start`, if there is one. Adjusted
+    // payments are counted by their unadjusted dates, as these are what a fixed end date
+    // is compared to (see `get_payment_dates`). A final payment that rolls forward moves
+    // the date to its business day, unless that would also take in the next payment.
+    pub(crate) fn get_nth_payment_date(&self, start: Date<Utc>, n: u32) -> Option<Date<Utc>> {
+        if n == 0 {
+            return None;
+        }
+
+        match *self {
+            Frequency::Adjusted(ref frequency, convention, ref calendar) => {
+                let mut dates = frequency.occurrences(start).skip(n as usize - 1);
+                let nth = dates.next()?;
+                let adjusted = match dates.next() {
+                    Some(next) => convention.adjust_for(nth, calendar).min(next.pred()),
+                    None => convention.adjust_for(nth, calendar),
+                };
+                Some(nth.max(adjusted))
+            }
+            _ => self.occurrences(start).nth(n as usize - 1),
         }
     }
 
@@ -85,6 +146,7 @@ impl Frequency {
         // inclusion of the first day of next period in this period. For example, Monday
         // + 1 week = Monday. However the _end_ of this period is *Sunday*.
         let period_end = start.pred() + self.get_period_length();
+        let fixed_end = end;
         let end = end.unwrap_or(period_end);
 
         match *self {
@@ -200,6 +262,25 @@ impl Frequency {
 
                 dates
             }
+            Frequency::Adjusted(ref frequency, convention, ref calendar) => {
+                // Adjusted payments can never occur before the start date, or after a
+                // fixed end date, as they would fall outside the model entirely.
+                // Otherwise they are allowed to roll beyond the end of the period.
+                let mut dates: Vec<Date<Utc>> = frequency
+                    .get_payment_dates(start, fixed_end)
+                    .into_iter()
+                    .map(|d| convention.adjust_for(d, calendar).max(start))
+                    .map(|d| match fixed_end {
+                        Some(e) => d.min(e),
+                        None => d,
+                    })
+                    .collect();
+
+                // Note that we don't dedup dates here, as multiple payments can be rolled
+                // onto the same business day.
+                dates.sort_unstable();
+                dates
+            }
         }
     }
 }
@@ -241,21 +322,7 @@ impl Display for Frequency {
             Frequency::MonthlyDate(n, ref m) => {
                 let dates = m
                     .iter()
-                    .map(|d| format!("{}", d))
-                    .map(|d| {
-                        if d.ends_with("11") || d.ends_with("12") || d.ends_with("13") {
-                            "th"
-                        } else if d.ends_with('1') {
-                            "st"
-                        } else if d.ends_with('2') {
-                            "nd"
-                        } else if d.ends_with('3') {
-                            "rd"
-                        } else {
-                            "th"
-                        }
-                    })
-                    .map(|d| d.to_owned())
+                    .map(|d| to_ordinal(*d))
                     .reduce(|a, b| a + ", " + &b)
                     .expect("dates vector is empty");
                 if n == 1 {
@@ -302,7 +369,103 @@ impl Display for Frequency {
             }
             // Any other permutations of `Yearly` are not allowed
             Frequency::Yearly(_, _, _, _) => unreachable!(),
+            Frequency::Adjusted(ref frequency, convention, _) => match convention {
+                BusinessDayConvention::Unadjusted => write!(f, "{}", frequency),
+                BusinessDayConvention::Following => {
+                    write!(f, "{}, paid on the following business day", frequency)
+                }
+                BusinessDayConvention::ModifiedFollowing => write!(
+                    f,
+                    "{}, paid on the following business day in the same month",
+                    frequency
+                ),
+                BusinessDayConvention::Preceding => {
+                    write!(f, "{}, paid on the preceding business day", frequency)
+                }
+                BusinessDayConvention::ModifiedPreceding => write!(
+                    f,
+                    "{}, paid on the preceding business day in the same month",
+                    frequency
+                ),
+            },
+        }
+    }
+}
+
+// An iterator over the payment dates of a `Frequency`. See `Frequency::occurrences`.
+//
+// Payment dates are generated by the underlying (unadjusted) frequency for a
+// progressively doubling number of periods, which keeps each date anchored to the
+// original start date. Any business day adjustments are applied as dates are yielded, as
+// rolling a date never changes its order.
+struct Occurrences<'a> {
+    frequency: &'a Frequency,
+    adjustments: Vec<(BusinessDayConvention, &'a HolidayCalendar)>,
+    start: Date<Utc>,
+    periods: i32,
+    dates: Vec<Date<Utc>>,
+    next: usize,
+    done: bool,
+}
+
+impl<'a> Occurrences<'a> {
+    fn new(mut frequency: &'a Frequency, start: Date<Utc>) -> Self {
+        let mut adjustments = Vec::new();
+        while let Frequency::Adjusted(ref inner, convention, ref calendar) = *frequency {
+            adjustments.push((convention, calendar));
+            frequency = inner;
+        }
+
+        // The innermost adjustment is applied first
+        adjustments.reverse();
+
+        Occurrences {
+            frequency,
+            adjustments,
+            start,
+            periods: 0,
+            dates: Vec::new(),
+            next: 0,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = Date<Utc>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next == self.dates.len() {
+            if self.done {
+                return None;
+            }
+
+            self.periods = (self.periods * 2).max(1);
+            let period_length = self.frequency.get_period_length();
+            let end = self.start.pred() + period_length * self.periods;
+            let dates = self.frequency.get_payment_dates(self.start, Some(end));
+
+            // A single payment never repeats. Otherwise, if we've searched more than a
+            // couple of periods past the last payment and still found nothing, the
+            // frequency has no more dates to give.
+            let last = self.dates.last().copied().unwrap_or(self.start);
+            self.done = self.frequency.is_once()
+                || (dates.len() == self.dates.len()
+                    && end - last > (period_length + Duration::days(MACRO_PERIOD as i64)) * 2);
+            self.dates = dates;
         }
+
+        let date = self.dates[self.next];
+        self.next += 1;
+
+        Some(
+            self.adjustments
+                .iter()
+                .fold(date, |d, (convention, calendar)| {
+                    convention.adjust_for(d, calendar)
+                })
+                .max(self.start),
+        )
     }
 }
 
@@ -324,7 +487,7 @@ impl FrequencyMonthDay {
         }
     }
 
-    fn get_date(&self, year: i32, month: u32, mut nth: u32) -> Option<Date<Utc>> {
+    pub(crate) fn get_date(&self, year: i32, month: u32, mut nth: u32) -> Option<Date<Utc>> {
         let date = Utc.ymd(year, month, 1);
         let weekday = date.weekday().number_from_monday();
         let seek_last = nth == 0;
@@ -338,8 +501,10 @@ impl FrequencyMonthDay {
 
         // The maximum length for 4 weeks is 28 days. If there is surplus, this means
         // that some days can accommodate a 5th recursion, if they occur early enough
-        // in the month.
-        let max_nth = if self.get_day_of_week() <= length - 28 {
+        // in the month. Plain days are only bounded by the length of the month.
+        let max_nth = if let FrequencyMonthDay::Day = self {
+            length
+        } else if self.get_day_of_week() <= length - 28 {
             5
         } else {
             4
@@ -547,19 +712,32 @@ fn increment_to_weekday(date: Date<Utc>, mut day: u32, interval: Duration) -> Da
     date + seek_duration
 }
 
-// Convert an 'nth' number to a string for display purposes
-fn nth_to_str(nth: u32) -> &'static str {
+// Convert an 'nth' number to a string for display purposes. Numbers above 5 can only be
+// days of the month (e.g. the 15th day), as there are a maximum of 5 weeks in a month.
+fn nth_to_str(nth: u32) -> String {
     match nth {
-        0 => "last",
-        1 => "first",
-        2 => "second",
-        3 => "third",
-        4 => "fourth",
-        5 => "fifth",
-        _ => unreachable!(), // maximum of 5 weeks in a month
+        0 => "last".into(),
+        1 => "first".into(),
+        2 => "second".into(),
+        3 => "third".into(),
+        4 => "fourth".into(),
+        5 => "fifth".into(),
+        _ => to_ordinal(nth),
     }
 }
 
+// Convert a number to an ordinal string (e.g. 1st, 12th, 22nd) for display purposes
+fn to_ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
 // Convert a vector of month integers to a string for display purposes
 fn months_to_str(months: &[u32]) -> String {
     months
@@ -587,6 +765,7 @@ fn months_to_str(months: &[u32]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::holiday::{Holiday, HolidayRule};
     use chrono::{Duration, TimeZone, Utc};
 
     #[test]
@@ -661,6 +840,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_period_length_adjusted() {
+        let freq = Frequency::Adjusted(
+            Box::new(Frequency::Weekly(2, vec![6])),
+            BusinessDayConvention::Following,
+            HolidayCalendar::default(),
+        );
+        assert_eq!(freq.get_period_length(), Duration::weeks(2));
+    }
+
     #[test]
     fn get_dates_for_interval_5_days() {
         let interval = Duration::days(5);
@@ -754,6 +943,19 @@ mod tests {
         assert_eq!(frequency.get_date(2000, 4, 4), Some(new_date));
     }
 
+    #[test]
+    fn get_date_day_mid_month() {
+        let frequency = FrequencyMonthDay::Day;
+        let new_date = Utc.ymd(2000, 4, 15);
+        assert_eq!(frequency.get_date(2000, 4, 15), Some(new_date));
+    }
+
+    #[test]
+    fn get_date_day_invalid() {
+        let frequency = FrequencyMonthDay::Day;
+        assert_eq!(frequency.get_date(2001, 2, 29), None);
+    }
+
     #[test]
     fn get_date_weekday_today() {
         let frequency = FrequencyMonthDay::Weekday;
@@ -1110,6 +1312,247 @@ mod tests {
         assert_eq!(frequency.get_payment_dates(start, None), dates);
     }
 
+    #[test]
+    fn get_payment_dates_adjusted_following() {
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::MonthlyDate(1, vec![1])),
+            BusinessDayConvention::Following,
+            HolidayCalendar::default(),
+        );
+        let start = Utc.ymd(2000, 4, 1);
+        let end = Utc.ymd(2000, 7, 31);
+        let dates = vec![
+            Utc.ymd(2000, 4, 3),
+            Utc.ymd(2000, 5, 1),
+            Utc.ymd(2000, 6, 1),
+            Utc.ymd(2000, 7, 3),
+        ];
+
+        assert_eq!(frequency.get_payment_dates(start, Some(end)), dates);
+    }
+
+    #[test]
+    fn occurrences_weekly_multi_day() {
+        let frequency = Frequency::Weekly(2, vec![2, 5]);
+        let start = Utc.ymd(2000, 4, 1); // Saturday
+        let dates: Vec<Date<Utc>> = frequency.occurrences(start).take(5).collect();
+
+        assert_eq!(
+            dates,
+            vec![
+                Utc.ymd(2000, 4, 4),
+                Utc.ymd(2000, 4, 7),
+                Utc.ymd(2000, 4, 18),
+                Utc.ymd(2000, 4, 21),
+                Utc.ymd(2000, 5, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn occurrences_yearly_nth_day() {
+        // Last Monday in May, every year
+        let frequency = Frequency::Yearly(1, vec![5], Some(0), Some(FrequencyMonthDay::Monday));
+        let dates: Vec<Date<Utc>> = frequency
+            .occurrences(Utc.ymd(2000, 4, 1))
+            .skip(10)
+            .take(2)
+            .collect();
+
+        assert_eq!(dates, vec![Utc.ymd(2010, 5, 31), Utc.ymd(2011, 5, 30)]);
+    }
+
+    #[test]
+    fn occurrences_match_payment_dates() {
+        let frequencies = vec![
+            Frequency::Daily(3),
+            Frequency::Weekly(3, vec![1, 4, 7]),
+            Frequency::MonthlyDate(2, vec![1, 31]),
+            Frequency::MonthlyDay(1, 0, FrequencyMonthDay::Weekday),
+            Frequency::Yearly(2, vec![2, 8], Some(29), Some(FrequencyMonthDay::Day)),
+        ];
+        let start = Utc.ymd(2000, 4, 1);
+        let end = Utc.ymd(2009, 12, 31);
+
+        for frequency in frequencies {
+            assert_eq!(
+                frequency
+                    .occurrences_between(start, end)
+                    .collect::<Vec<_>>(),
+                frequency.get_payment_dates(start, Some(end)),
+                "{:?}",
+                frequency
+            );
+        }
+    }
+
+    #[test]
+    fn occurrences_once() {
+        let mut dates = Frequency::Once.occurrences(Utc.ymd(2000, 4, 1));
+        assert_eq!(dates.next(), Some(Utc.ymd(2000, 4, 1)));
+        assert_eq!(dates.next(), None);
+    }
+
+    #[test]
+    fn occurrences_adjusted() {
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::MonthlyDate(1, vec![1])),
+            BusinessDayConvention::Following,
+            HolidayCalendar::default(),
+        );
+        let dates: Vec<Date<Utc>> = frequency
+            .occurrences_between(Utc.ymd(2000, 4, 1), Utc.ymd(2000, 7, 31))
+            .collect();
+
+        assert_eq!(
+            dates,
+            vec![
+                Utc.ymd(2000, 4, 3),
+                Utc.ymd(2000, 5, 1),
+                Utc.ymd(2000, 6, 1),
+                Utc.ymd(2000, 7, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn occurrences_never() {
+        let frequency = Frequency::MonthlyDate(12, vec![31]);
+        assert_eq!(frequency.occurrences(Utc.ymd(2000, 4, 1)).next(), None);
+    }
+
+    #[test]
+    fn get_nth_payment_date() {
+        let start = Utc.ymd(2000, 4, 1);
+        let frequency = Frequency::Weekly(1, vec![2, 5]);
+
+        assert_eq!(frequency.get_nth_payment_date(start, 0), None);
+        assert_eq!(
+            frequency.get_nth_payment_date(start, 1),
+            Some(Utc.ymd(2000, 4, 4))
+        );
+        assert_eq!(
+            frequency.get_nth_payment_date(start, 9),
+            Some(Utc.ymd(2000, 5, 2))
+        );
+        assert_eq!(Frequency::Once.get_nth_payment_date(start, 2), None);
+        assert_eq!(
+            Frequency::MonthlyDate(1, vec![31]).get_nth_payment_date(start, 2),
+            Some(Utc.ymd(2000, 7, 31))
+        );
+    }
+
+    #[test]
+    fn get_nth_payment_date_adjusted() {
+        // 1 July 2000 is a Saturday, which is rolled back to Friday 30 June
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::MonthlyDate(1, vec![1])),
+            BusinessDayConvention::Preceding,
+            HolidayCalendar::default(),
+        );
+        let start = Utc.ymd(2000, 4, 1);
+        let end = frequency.get_nth_payment_date(start, 4);
+
+        assert_eq!(end, Some(Utc.ymd(2000, 7, 1)));
+        assert_eq!(
+            frequency.get_payment_dates(start, end),
+            vec![
+                Utc.ymd(2000, 4, 1),
+                Utc.ymd(2000, 5, 1),
+                Utc.ymd(2000, 6, 1),
+                Utc.ymd(2000, 6, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_nth_payment_date_following() {
+        // 1 July 2000 is a Saturday, which is rolled forward to Monday 3 July
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::MonthlyDate(1, vec![1])),
+            BusinessDayConvention::Following,
+            HolidayCalendar::default(),
+        );
+        let start = Utc.ymd(2000, 5, 1);
+        let end = frequency.get_nth_payment_date(start, 3);
+
+        assert_eq!(end, Some(Utc.ymd(2000, 7, 3)));
+        assert_eq!(
+            frequency.get_payment_dates(start, end),
+            vec![
+                Utc.ymd(2000, 5, 1),
+                Utc.ymd(2000, 6, 1),
+                Utc.ymd(2000, 7, 3)
+            ]
+        );
+
+        // Sunday's payment is also made on Monday, so the 5th payment can't move there
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::Daily(1)),
+            BusinessDayConvention::Following,
+            HolidayCalendar::default(),
+        );
+        let start = Utc.ymd(2000, 6, 27);
+        let end = frequency.get_nth_payment_date(start, 5);
+
+        assert_eq!(end, Some(Utc.ymd(2000, 7, 1)));
+        assert_eq!(frequency.get_payment_dates(start, end).len(), 5);
+    }
+
+    #[test]
+    fn get_nth_payment_date_never() {
+        let frequency = Frequency::Yearly(1, vec![2], Some(30), Some(FrequencyMonthDay::Day));
+        assert_eq!(frequency.get_nth_payment_date(Utc.ymd(2000, 4, 1), 1), None);
+    }
+
+    #[test]
+    fn get_payment_dates_adjusted_holidays() {
+        let calendar = HolidayCalendar::new(vec![Holiday::new(
+            "New Year's Day",
+            HolidayRule::Observed(Box::new(HolidayRule::Fixed(1, 1))),
+        )]);
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::MonthlyDate(1, vec![1])),
+            BusinessDayConvention::Following,
+            calendar,
+        );
+        let start = Utc.ymd(2000, 12, 1);
+        let end = Utc.ymd(2001, 1, 31);
+        let dates = vec![Utc.ymd(2000, 12, 1), Utc.ymd(2001, 1, 2)];
+
+        assert_eq!(frequency.get_payment_dates(start, Some(end)), dates);
+    }
+
+    #[test]
+    fn get_payment_dates_adjusted_bounds() {
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::Daily(1)),
+            BusinessDayConvention::Preceding,
+            HolidayCalendar::default(),
+        );
+        let start = Utc.ymd(2000, 4, 1); // Saturday
+        let end = Utc.ymd(2000, 4, 4);
+        let dates = vec![start, start, Utc.ymd(2000, 4, 3), end];
+
+        assert_eq!(frequency.get_payment_dates(start, Some(end)), dates);
+    }
+
+    #[test]
+    fn get_payment_dates_adjusted_once() {
+        let frequency = Frequency::Adjusted(
+            Box::new(Frequency::Once),
+            BusinessDayConvention::Following,
+            HolidayCalendar::default(),
+        );
+        let start = Utc.ymd(2000, 4, 1);
+
+        assert!(frequency.is_once());
+        assert_eq!(
+            frequency.get_payment_dates(start, None),
+            vec![Utc.ymd(2000, 4, 3)]
+        );
+    }
+
     #[test]
     fn get_payment_dates_yearly_odd_years() {
         let frequency = Frequency::Yearly(2, vec![1, 2], None, None);
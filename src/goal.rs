@@ -0,0 +1,349 @@
+use crate::{
+    amounts::AmountSchedule,
+    clock::Clock,
+    currency::CurrencyError,
+    frequency::Frequency,
+    money::Money,
+    rounding::RoundingPolicy,
+    transaction::{affordability_report, Termination, TransactionError, TransactionModel},
+};
+use chrono::{Date, Utc};
+use log::debug;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A target amount to save by a target date.
+///
+/// Rather than working out the periodic amount to save, a `SavingsGoal` derives the
+/// deposits required to reach the target and produces a [`TransactionModel`] that can
+/// be passed as savings to [`is_affordable`](crate::is_affordable) and friends.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SavingsGoal {
+    target: Money,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date"))]
+    target_date: Date<Utc>,
+    saved: Decimal,
+    frequency: Option<Frequency>,
+}
+
+/// Errors encountered whilst planning a [`SavingsGoal`].
+#[derive(Error, Debug, PartialEq)]
+pub enum SavingsGoalError {
+    #[error(transparent)]
+    Currency(#[from] CurrencyError),
+    #[error("could not create savings model: {0}")]
+    Transaction(#[from] TransactionError),
+    #[error("the target date {0} has passed")]
+    Expired(Date<Utc>),
+    #[error("there are no deposits before the target date {0}")]
+    NoDeposits(Date<Utc>),
+}
+
+impl SavingsGoal {
+    /// Create a new `SavingsGoal` to save the `target` amount by the `target_date`
+    /// (inclusive).
+    ///
+    /// Any amount that has already been `saved` is deducted from the target. If a
+    /// deposit `frequency` is provided, the remaining amount is split evenly across
+    /// each deposit before the target date. Otherwise the remaining amount is saved for
+    /// as a single deposit on the target date, with contributions spread across each day
+    /// until then.
+    pub fn new(
+        target: Money,
+        target_date: Date<Utc>,
+        saved: Option<Money>,
+        frequency: Option<Frequency>,
+    ) -> Result<Self, SavingsGoalError> {
+        let saved = match saved {
+            Some(saved) => {
+                saved.check_currency(target.get_currency())?;
+                saved.get_amount()
+            }
+            None => Decimal::ZERO,
+        };
+
+        Ok(SavingsGoal {
+            target,
+            target_date,
+            saved,
+            frequency,
+        })
+    }
+
+    /// The amount to save.
+    pub fn get_target(&self) -> Money {
+        self.target
+    }
+
+    /// The date to save the target amount by.
+    pub fn get_target_date(&self) -> Date<Utc> {
+        self.target_date
+    }
+
+    /// The amount that has already been saved towards the target.
+    pub fn get_saved(&self) -> Money {
+        Money::new(self.saved, self.target.get_currency())
+    }
+
+    /// How often deposits are made, if they are not a single deposit on the target date.
+    pub fn get_frequency(&self) -> Option<&Frequency> {
+        self.frequency.as_ref()
+    }
+
+    /// The amount left to save, which is zero once the goal has been met.
+    pub fn get_remaining(&self) -> Money {
+        Money::new(
+            (self.target.get_amount() - self.saved).max(Decimal::ZERO),
+            self.target.get_currency(),
+        )
+    }
+
+    /// Whether the amount already saved meets the target.
+    pub fn is_met(&self) -> bool {
+        self.get_remaining().get_amount() == Decimal::ZERO
+    }
+
+    /// The amount of each deposit from `today` until the target date, in date order.
+    ///
+    /// Deposits are rounded up to the currency's minor unit, so the final deposit may be
+    /// smaller than the others. Returns no deposits if the goal has already been met.
+    pub fn get_deposits(
+        &self,
+        today: Date<Utc>,
+    ) -> Result<Vec<(Date<Utc>, Money)>, SavingsGoalError> {
+        let remaining = self.get_remaining();
+        if self.is_met() {
+            return Ok(Vec::new());
+        }
+        if self.target_date < today {
+            return Err(SavingsGoalError::Expired(self.target_date));
+        }
+
+        let dates: Vec<Date<Utc>> = match &self.frequency {
+            Some(frequency) => frequency
+                .occurrences_between(today, self.target_date)
+                .collect(),
+            None => vec![self.target_date],
+        };
+        if dates.is_empty() {
+            return Err(SavingsGoalError::NoDeposits(self.target_date));
+        }
+
+        let currency = remaining.get_currency();
+        let regular = RoundingPolicy::RoundUp.round(
+            remaining.get_amount() / Decimal::from(dates.len()),
+            currency,
+        );
+        let mut left = remaining.get_amount();
+
+        Ok(dates
+            .into_iter()
+            .map(|date| {
+                let amount = regular.min(left);
+                left -= amount;
+                (date, Money::new(amount, currency))
+            })
+            .filter(|(_, amount)| amount.get_amount() > Decimal::ZERO)
+            .collect())
+    }
+
+    /// Create the savings [`TransactionModel`] that reaches this goal, calculated from
+    /// the `clock`'s date. Returns `None` if the goal has already been met.
+    pub fn to_model(
+        &self,
+        clock: &dyn Clock,
+    ) -> Result<Option<TransactionModel>, SavingsGoalError> {
+        let today = clock.today();
+        let deposits = self.get_deposits(today)?;
+        let last = match deposits.last() {
+            Some((date, _)) => *date,
+            None => return Ok(None),
+        };
+
+        debug!(
+            "saving {} by {} in {} deposits",
+            self.get_remaining(),
+            self.target_date,
+            deposits.len()
+        );
+
+        let model = match &self.frequency {
+            Some(frequency) => TransactionModel::new_variable(
+                deposits[0].1,
+                AmountSchedule::Dates(
+                    deposits
+                        .iter()
+                        .map(|(date, amount)| (*date, amount.get_amount()))
+                        .collect(),
+                ),
+                frequency.clone(),
+                today,
+                Termination::EndDate(last),
+                RoundingPolicy::Exact,
+                clock,
+            ),
+            None => TransactionModel::new_with_clock(
+                deposits[0].1,
+                None,
+                Frequency::Once,
+                last,
+                Termination::Never,
+                RoundingPolicy::Exact,
+                clock,
+            ),
+        }?;
+
+        Ok(Some(model))
+    }
+
+    /// Whether this goal can be reached given current affordability.
+    ///
+    /// The goal is reachable if adding its savings model to the other revenue, expense
+    /// and savings models causes no deficit on any day up to the target date. Use
+    /// [`affordability_report`] with [`SavingsGoal::to_model`] for the details of any
+    /// deficit.
+    pub fn is_reachable(
+        &self,
+        revenues: Option<&[TransactionModel]>,
+        expenses: Option<&[TransactionModel]>,
+        savings: Option<&[TransactionModel]>,
+        clock: &dyn Clock,
+    ) -> Result<bool, SavingsGoalError> {
+        let mut all_savings = savings.unwrap_or_default().to_vec();
+        all_savings.extend(self.to_model(clock)?);
+
+        let report = affordability_report(revenues, expenses, Some(&all_savings))?;
+        Ok(report
+            .get_first_deficit_date()
+            .is_none_or(|date| date > self.target_date))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::FixedClock, currency::Currency};
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn goal_deposits() {
+        let goal = SavingsGoal::new(
+            Money::aud(dec!(1000)),
+            Utc.ymd(2000, 6, 30),
+            Some(Money::aud(dec!(100))),
+            Some(Frequency::MonthlyDate(1, vec![15])),
+        )
+        .unwrap();
+
+        assert_eq!(goal.get_remaining(), Money::aud(dec!(900)));
+        assert_eq!(
+            goal.get_deposits(Utc.ymd(2000, 4, 1)).unwrap(),
+            vec![
+                (Utc.ymd(2000, 4, 15), Money::aud(dec!(300))),
+                (Utc.ymd(2000, 5, 15), Money::aud(dec!(300))),
+                (Utc.ymd(2000, 6, 15), Money::aud(dec!(300))),
+            ]
+        );
+
+        // Deposits are rounded up, with the final deposit making up the difference
+        let goal = SavingsGoal::new(
+            Money::aud(dec!(100)),
+            Utc.ymd(2000, 6, 30),
+            None,
+            Some(Frequency::MonthlyDate(1, vec![15])),
+        )
+        .unwrap();
+        let deposits = goal.get_deposits(Utc.ymd(2000, 4, 1)).unwrap();
+        assert_eq!(deposits[0].1, Money::aud(dec!(33.34)));
+        assert_eq!(deposits[2].1, Money::aud(dec!(33.32)));
+
+        assert_eq!(
+            goal.get_deposits(Utc.ymd(2000, 7, 1)),
+            Err(SavingsGoalError::Expired(Utc.ymd(2000, 6, 30)))
+        );
+        assert_eq!(
+            goal.get_deposits(Utc.ymd(2000, 6, 16)),
+            Err(SavingsGoalError::NoDeposits(Utc.ymd(2000, 6, 30)))
+        );
+    }
+
+    #[test]
+    fn goal_to_model() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let goal =
+            SavingsGoal::new(Money::aud(dec!(300)), Utc.ymd(2000, 4, 30), None, None).unwrap();
+
+        let model = goal.to_model(&clock).unwrap().unwrap();
+        assert_eq!(model.get_value(), Money::aud(dec!(300)));
+        assert_eq!(model.get_start_date(), Some(Utc.ymd(2000, 4, 1)));
+        assert_eq!(
+            model.get_contributions()[0].get_regular(),
+            Money::aud(dec!(10))
+        );
+
+        let goal = SavingsGoal::new(
+            Money::aud(dec!(100)),
+            Utc.ymd(2000, 6, 30),
+            None,
+            Some(Frequency::MonthlyDate(1, vec![15])),
+        )
+        .unwrap();
+        let model = goal.to_model(&clock).unwrap().unwrap();
+        assert_eq!(model.get_end_date(), Some(Utc.ymd(2000, 6, 15)));
+        assert_eq!(
+            model.get_payment_amount(Utc.ymd(2000, 6, 15)),
+            Money::aud(dec!(33.32))
+        );
+
+        let goal = SavingsGoal::new(
+            Money::aud(dec!(100)),
+            Utc.ymd(2000, 6, 30),
+            Some(Money::aud(dec!(150))),
+            None,
+        )
+        .unwrap();
+        assert!(goal.is_met());
+        assert_eq!(goal.to_model(&clock), Ok(None));
+    }
+
+    #[test]
+    fn goal_reachable() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let revenues = vec![TransactionModel::new(
+            Money::aud(dec!(20)),
+            None,
+            Frequency::Daily(1),
+            clock.today(),
+            None,
+            Some(clock.today()),
+        )
+        .unwrap()];
+
+        let goal =
+            SavingsGoal::new(Money::aud(dec!(300)), Utc.ymd(2000, 4, 30), None, None).unwrap();
+        assert!(goal
+            .is_reachable(Some(&revenues), None, None, &clock)
+            .unwrap());
+
+        let goal =
+            SavingsGoal::new(Money::aud(dec!(900)), Utc.ymd(2000, 4, 30), None, None).unwrap();
+        assert!(!goal
+            .is_reachable(Some(&revenues), None, None, &clock)
+            .unwrap());
+    }
+
+    #[test]
+    fn goal_currency_mismatch() {
+        let result = SavingsGoal::new(
+            Money::aud(dec!(100)),
+            Utc.ymd(2000, 6, 30),
+            Some(Money::new(dec!(10), Currency::USD)),
+            None,
+        );
+        assert!(matches!(result, Err(SavingsGoalError::Currency(_))));
+    }
+}
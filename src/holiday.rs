@@ -0,0 +1,522 @@
+use std::{collections::BTreeSet, fs, io, path::Path};
+
+use crate::frequency::FrequencyMonthDay;
+use chrono::{Date, Datelike, Duration, TimeZone, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// The furthest an Easter holiday can be from Easter Sunday, in days
+const MAX_EASTER_OFFSET: i64 = 366;
+
+/// A set of non-business days, in addition to weekends.
+///
+/// Calendars are usually built from a list of [`Holiday`]s, either in code or by loading
+/// a calendar file (see [`HolidayCalendar::parse`]). Regional calendars can then be
+/// combined, for example to find days that are holidays in either of two states.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HolidayCalendar {
+    /// A list of holidays
+    Holidays(Vec<Holiday>),
+    /// Days that are holidays in any of the calendars
+    Union(Vec<HolidayCalendar>),
+    /// Days that are holidays in all of the calendars
+    Intersection(Vec<HolidayCalendar>),
+}
+
+/// A named holiday that occurs every year.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Holiday {
+    name: String,
+    rule: HolidayRule,
+}
+
+/// The rule used to find the date of a `Holiday` in a given year.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HolidayRule {
+    /// The same `month` and `day` every year
+    Fixed(u32, u32),
+    /// The `nth` day of the `month`:
+    ///
+    /// _`nth` = 1 (first), 2 (second), ..., 5 (fifth), 0 (last)_
+    MonthDay(u32, u32, FrequencyMonthDay),
+    /// `n` days before (negative) or after (positive) Easter Sunday, up to a year either
+    /// side
+    Easter(i64),
+    /// A holiday that falls on a weekend is observed on the following Monday instead.
+    /// If that day is already a holiday, it is observed on the next available weekday.
+    Observed(Box<HolidayRule>),
+}
+
+/// Errors encountered whilst loading a `HolidayCalendar`.
+#[derive(Error, Debug)]
+pub enum HolidayError {
+    #[error("could not read calendar file")]
+    Io(#[from] io::Error),
+    #[error("invalid calendar entry on line {0}: {1}")]
+    InvalidEntry(usize, String),
+    #[error("invalid holiday rule '{0}'")]
+    InvalidRule(String),
+}
+
+impl HolidayCalendar {
+    /// Create a calendar from a list of holidays.
+    pub fn new(holidays: Vec<Holiday>) -> Self {
+        HolidayCalendar::Holidays(holidays)
+    }
+
+    /// Parse a calendar from a string.
+    ///
+    /// Each holiday is listed on its own line, as a name and a rule separated by `=`.
+    /// Names may be quoted, and rules must be. Blank lines, comments (starting with `#`)
+    /// and a `[holidays]` header are ignored, which means that a calendar file is also a
+    /// valid TOML document:
+    ///
+    /// ```text
+    /// [holidays]
+    /// "New Year's Day" = "observed 01-01"
+    /// "Good Friday" = "easter -2"
+    /// "Easter Monday" = "easter +1"
+    /// "Labour Day" = "first monday 05"
+    /// "Christmas Day" = "observed 12-25"
+    /// ```
+    ///
+    /// Rules are one of:
+    /// - `MM-DD` - a fixed date
+    /// - `<nth> <day> MM` - the nth day of a month, where `nth` is `first`, `second`,
+    ///   `third`, `fourth`, `fifth` or `last`, and `day` is a day of the week, `day`,
+    ///   `weekday` or `weekend`
+    /// - `easter <offset>` - a number of days relative to Easter Sunday
+    /// - `observed <rule>` - any of the above, moved off the weekend
+    pub fn parse(calendar: &str) -> Result<Self, HolidayError> {
+        let mut holidays = Vec::new();
+
+        for (i, line) in calendar.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line == "[holidays]" {
+                continue;
+            }
+
+            let invalid = |reason: &str| HolidayError::InvalidEntry(i + 1, reason.into());
+
+            let (name, rule) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("expected '<name> = \"<rule>\"'"))?;
+            let name = unquote(name.trim()).unwrap_or_else(|| name.trim());
+            let rule = unquote(rule.trim()).ok_or_else(|| invalid("rule must be quoted"))?;
+
+            if name.is_empty() {
+                return Err(invalid("missing holiday name"));
+            }
+
+            holidays.push(Holiday::new(name, HolidayRule::parse(rule)?));
+        }
+
+        Ok(HolidayCalendar::Holidays(holidays))
+    }
+
+    /// Load a calendar from a file. See [`HolidayCalendar::parse`] for the format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, HolidayError> {
+        HolidayCalendar::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Combine this calendar with another, such that a day is a holiday if it is a
+    /// holiday in either calendar.
+    pub fn union(self, other: HolidayCalendar) -> Self {
+        match self {
+            HolidayCalendar::Union(mut calendars) => {
+                calendars.push(other);
+                HolidayCalendar::Union(calendars)
+            }
+            _ => HolidayCalendar::Union(vec![self, other]),
+        }
+    }
+
+    /// Combine this calendar with another, such that a day is a holiday only if it is a
+    /// holiday in both calendars.
+    pub fn intersection(self, other: HolidayCalendar) -> Self {
+        match self {
+            HolidayCalendar::Intersection(mut calendars) => {
+                calendars.push(other);
+                HolidayCalendar::Intersection(calendars)
+            }
+            _ => HolidayCalendar::Intersection(vec![self, other]),
+        }
+    }
+
+    /// All holidays that fall in the given year, in date order.
+    pub fn get_holidays(&self, year: i32) -> Vec<Date<Utc>> {
+        self.get_holiday_set(year).into_iter().collect()
+    }
+
+    /// Whether the given date is a holiday.
+    pub fn is_holiday(&self, date: Date<Utc>) -> bool {
+        self.get_holiday_set(date.year()).contains(&date)
+    }
+
+    /// Whether the given date is a business day, i.e. a weekday that isn't a holiday.
+    pub fn is_business_day(&self, date: Date<Utc>) -> bool {
+        date.weekday().number_from_monday() <= 5 && !self.is_holiday(date)
+    }
+
+    fn get_holiday_set(&self, year: i32) -> BTreeSet<Date<Utc>> {
+        match *self {
+            HolidayCalendar::Holidays(ref holidays) => {
+                // Observed holidays from the end of last year can fall in this year, as
+                // can Easter offsets from next year's Easter
+                let mut dates = get_observed_dates(holidays, year - 1);
+                dates.append(&mut get_observed_dates(holidays, year));
+                dates.append(&mut get_observed_dates(holidays, year + 1));
+                dates.retain(|d| d.year() == year);
+                dates
+            }
+            HolidayCalendar::Union(ref calendars) => calendars
+                .iter()
+                .flat_map(|c| c.get_holiday_set(year))
+                .collect(),
+            HolidayCalendar::Intersection(ref calendars) => calendars
+                .iter()
+                .map(|c| c.get_holiday_set(year))
+                .reduce(|a, b| a.intersection(&b).copied().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for HolidayCalendar {
+    /// A calendar with no holidays.
+    fn default() -> Self {
+        HolidayCalendar::Holidays(Vec::new())
+    }
+}
+
+impl Holiday {
+    pub fn new<S: Into<String>>(name: S, rule: HolidayRule) -> Self {
+        Holiday {
+            name: name.into(),
+            rule,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_rule(&self) -> &HolidayRule {
+        &self.rule
+    }
+}
+
+impl HolidayRule {
+    /// Get the date that this rule falls on in the given year, if any.
+    ///
+    /// Note that `Observed` rules are only moved off the weekend here. Clashes with other
+    /// holidays are resolved by the `HolidayCalendar`.
+    pub fn get_date(&self, year: i32) -> Option<Date<Utc>> {
+        match *self {
+            HolidayRule::Fixed(month, day) => Utc.ymd_opt(year, month, day).single(),
+            HolidayRule::MonthDay(month, nth, ref day) => {
+                if (1..=12).contains(&month) {
+                    day.get_date(year, month, nth)
+                } else {
+                    None
+                }
+            }
+            HolidayRule::Easter(offset) if offset.abs() <= MAX_EASTER_OFFSET => {
+                Some(get_easter_sunday(year) + Duration::days(offset))
+            }
+            HolidayRule::Easter(_) => None,
+            HolidayRule::Observed(ref rule) => rule.get_date(year).map(|mut date| {
+                while date.weekday().number_from_monday() > 5 {
+                    date = date.succ();
+                }
+                date
+            }),
+        }
+    }
+
+    fn is_observed(&self) -> bool {
+        matches!(self, HolidayRule::Observed(_))
+    }
+
+    fn parse(rule: &str) -> Result<Self, HolidayError> {
+        let invalid = || HolidayError::InvalidRule(rule.into());
+        let parts: Vec<&str> = rule.split_whitespace().collect();
+
+        match parts[..] {
+            ["observed", ..] => {
+                let inner = HolidayRule::parse(&parts[1..].join(" ")).map_err(|_| invalid())?;
+                Ok(HolidayRule::Observed(Box::new(inner)))
+            }
+            ["easter"] => Ok(HolidayRule::Easter(0)),
+            ["easter", offset] => offset
+                .trim_start_matches('+')
+                .parse::<i64>()
+                .ok()
+                .filter(|offset| offset.abs() <= MAX_EASTER_OFFSET)
+                .map(HolidayRule::Easter)
+                .ok_or_else(invalid),
+            [date] => {
+                let (month, day) = date.split_once('-').ok_or_else(invalid)?;
+                let month = parse_month(month).ok_or_else(invalid)?;
+                let day = day.parse().map_err(|_| invalid())?;
+
+                // Validate against a leap year, so that 29 February is allowed
+                Utc.ymd_opt(2000, month, day).single().ok_or_else(invalid)?;
+                Ok(HolidayRule::Fixed(month, day))
+            }
+            [nth, day, month] => {
+                let nth = match nth {
+                    "first" => 1,
+                    "second" => 2,
+                    "third" => 3,
+                    "fourth" => 4,
+                    "fifth" => 5,
+                    "last" => 0,
+                    _ => return Err(invalid()),
+                };
+                let day = match day {
+                    "monday" => FrequencyMonthDay::Monday,
+                    "tuesday" => FrequencyMonthDay::Tuesday,
+                    "wednesday" => FrequencyMonthDay::Wednesday,
+                    "thursday" => FrequencyMonthDay::Thursday,
+                    "friday" => FrequencyMonthDay::Friday,
+                    "saturday" => FrequencyMonthDay::Saturday,
+                    "sunday" => FrequencyMonthDay::Sunday,
+                    "day" => FrequencyMonthDay::Day,
+                    "weekday" => FrequencyMonthDay::Weekday,
+                    "weekend" => FrequencyMonthDay::Weekend,
+                    _ => return Err(invalid()),
+                };
+                let month = parse_month(month).ok_or_else(invalid)?;
+                Ok(HolidayRule::MonthDay(month, nth, day))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// Get the dates of each holiday in the given year, moving observed holidays that clash
+// with another holiday to the next available weekday.
+fn get_observed_dates(holidays: &[Holiday], year: i32) -> BTreeSet<Date<Utc>> {
+    let (observed, fixed): (Vec<&Holiday>, Vec<&Holiday>) =
+        holidays.iter().partition(|h| h.rule.is_observed());
+
+    let mut dates: BTreeSet<Date<Utc>> =
+        fixed.iter().filter_map(|h| h.rule.get_date(year)).collect();
+
+    // Observed holidays are applied in the order they were listed
+    for holiday in observed {
+        if let Some(mut date) = holiday.rule.get_date(year) {
+            while dates.contains(&date) || date.weekday().number_from_monday() > 5 {
+                date = date.succ();
+            }
+            dates.insert(date);
+        }
+    }
+
+    dates
+}
+
+// Calculate Easter Sunday for the Gregorian calendar using the anonymous algorithm
+// (Meeus/Jones/Butcher).
+fn get_easter_sunday(year: i32) -> Date<Utc> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    Utc.ymd(year, month as u32, day as u32)
+}
+
+fn parse_month(month: &str) -> Option<u32> {
+    month.parse().ok().filter(|m| (1..=12).contains(m))
+}
+
+fn unquote(s: &str) -> Option<&str> {
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = r#"
+# National holidays
+[holidays]
+"New Year's Day" = "observed 01-01"
+"Good Friday" = "easter -2"
+"Easter Monday" = "easter +1"
+"Labour Day" = "first monday 05"
+"Christmas Day" = "observed 12-25"
+"Boxing Day" = "observed 12-26"
+"#;
+
+    #[test]
+    fn get_easter_sunday_dates() {
+        assert_eq!(get_easter_sunday(2000), Utc.ymd(2000, 4, 23));
+        assert_eq!(get_easter_sunday(2019), Utc.ymd(2019, 4, 21));
+        assert_eq!(get_easter_sunday(2024), Utc.ymd(2024, 3, 31));
+    }
+
+    #[test]
+    fn get_date_rules() {
+        assert_eq!(
+            HolidayRule::Fixed(2, 29).get_date(2000),
+            Some(Utc.ymd(2000, 2, 29))
+        );
+        assert_eq!(HolidayRule::Fixed(2, 29).get_date(2001), None);
+        assert_eq!(
+            HolidayRule::MonthDay(6, 2, FrequencyMonthDay::Monday).get_date(2000),
+            Some(Utc.ymd(2000, 6, 12))
+        );
+        assert_eq!(
+            HolidayRule::MonthDay(5, 0, FrequencyMonthDay::Monday).get_date(2000),
+            Some(Utc.ymd(2000, 5, 29))
+        );
+        assert_eq!(
+            HolidayRule::Easter(-2).get_date(2000),
+            Some(Utc.ymd(2000, 4, 21))
+        );
+        assert_eq!(
+            HolidayRule::Observed(Box::new(HolidayRule::Fixed(1, 1))).get_date(2000),
+            Some(Utc.ymd(2000, 1, 3))
+        );
+    }
+
+    #[test]
+    fn parse_calendar() {
+        let calendar = HolidayCalendar::parse(CALENDAR).unwrap();
+
+        if let HolidayCalendar::Holidays(ref holidays) = calendar {
+            assert_eq!(holidays.len(), 6);
+            assert_eq!(holidays[3].get_name(), "Labour Day");
+            assert_eq!(
+                holidays[3].get_rule(),
+                &HolidayRule::MonthDay(5, 1, FrequencyMonthDay::Monday)
+            );
+        } else {
+            panic!("expected a list of holidays");
+        }
+
+        // Christmas falls on a Saturday and Boxing Day on a Sunday
+        assert_eq!(
+            calendar.get_holidays(2021),
+            vec![
+                Utc.ymd(2021, 1, 1),
+                Utc.ymd(2021, 4, 2),
+                Utc.ymd(2021, 4, 5),
+                Utc.ymd(2021, 5, 3),
+                Utc.ymd(2021, 12, 27),
+                Utc.ymd(2021, 12, 28),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_calendar_invalid() {
+        assert!(matches!(
+            HolidayCalendar::parse("Christmas 12-25"),
+            Err(HolidayError::InvalidEntry(1, _))
+        ));
+        assert!(matches!(
+            HolidayCalendar::parse("\nChristmas = 12-25"),
+            Err(HolidayError::InvalidEntry(2, _))
+        ));
+        assert!(matches!(
+            HolidayCalendar::parse("Christmas = \"02-30\""),
+            Err(HolidayError::InvalidRule(_))
+        ));
+        assert!(matches!(
+            HolidayCalendar::parse("Labour Day = \"sixth monday 05\""),
+            Err(HolidayError::InvalidRule(_))
+        ));
+        assert!(matches!(
+            HolidayCalendar::parse("Someday = \"easter 9999999999\""),
+            Err(HolidayError::InvalidRule(_))
+        ));
+        assert_eq!(HolidayRule::Easter(-9999999999).get_date(2000), None);
+    }
+
+    #[test]
+    fn load_calendar_missing() {
+        assert!(matches!(
+            HolidayCalendar::load("/nonexistent/calendar.toml"),
+            Err(HolidayError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn observed_new_year_crosses_year() {
+        // 31 December 2005 is a Saturday
+        let calendar = HolidayCalendar::new(vec![Holiday::new(
+            "New Year's Eve",
+            HolidayRule::Observed(Box::new(HolidayRule::Fixed(12, 31))),
+        )]);
+
+        assert!(calendar.get_holidays(2005).is_empty());
+        assert_eq!(calendar.get_holidays(2006), vec![Utc.ymd(2006, 1, 2)]);
+        assert!(calendar.is_holiday(Utc.ymd(2006, 1, 2)));
+    }
+
+    #[test]
+    fn easter_offset_crosses_year() {
+        // Easter Sunday is 23 April 2000 and 15 April 2001
+        let calendar = HolidayCalendar::new(vec![Holiday::new("Early", HolidayRule::Easter(-120))]);
+
+        // 120 days before Easter 2000 is in 1999
+        assert_eq!(calendar.get_holidays(2000), vec![Utc.ymd(2000, 12, 16)]);
+        assert!(calendar.is_holiday(Utc.ymd(2000, 12, 16)));
+    }
+
+    #[test]
+    fn union_and_intersection() {
+        let a = HolidayCalendar::new(vec![
+            Holiday::new("A", HolidayRule::Fixed(3, 1)),
+            Holiday::new("Both", HolidayRule::Fixed(3, 2)),
+        ]);
+        let b = HolidayCalendar::new(vec![
+            Holiday::new("Both", HolidayRule::Fixed(3, 2)),
+            Holiday::new("B", HolidayRule::Fixed(3, 3)),
+        ]);
+
+        assert_eq!(
+            a.clone().union(b.clone()).get_holidays(2000),
+            vec![
+                Utc.ymd(2000, 3, 1),
+                Utc.ymd(2000, 3, 2),
+                Utc.ymd(2000, 3, 3)
+            ]
+        );
+        assert_eq!(
+            a.intersection(b).get_holidays(2000),
+            vec![Utc.ymd(2000, 3, 2)]
+        );
+    }
+
+    #[test]
+    fn is_business_day() {
+        let calendar = HolidayCalendar::new(vec![Holiday::new(
+            "Christmas Day",
+            HolidayRule::Fixed(12, 25),
+        )]);
+
+        assert!(calendar.is_business_day(Utc.ymd(2000, 12, 22)));
+        assert!(!calendar.is_business_day(Utc.ymd(2000, 12, 24)));
+        assert!(!calendar.is_business_day(Utc.ymd(2000, 12, 25)));
+        assert!(HolidayCalendar::default().is_business_day(Utc.ymd(2000, 12, 25)));
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    clock::Clock,
+    currency::Currency,
+    frequency::Frequency,
+    money::Money,
+    rounding::RoundingPolicy,
+    transaction::{
+        acc_daily_contributions, collect_contributions, get_common_currency, Termination,
+        TransactionError, TransactionModel,
+    },
+};
+use chrono::{Date, Datelike, Utc};
+use log::{debug, trace};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The interest paid on a savings balance, which may be tiered and may change over
+/// time. See [`project_interest`] for details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SavingsInterest {
+    compounding: SavingsCompounding,
+    tiers: Vec<RateTier>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date_map"))]
+    changes: BTreeMap<Date<Utc>, Vec<RateTier>>,
+}
+
+/// How often accrued interest is added to a savings balance, where it earns interest of
+/// its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SavingsCompounding {
+    /// Interest accrued each day earns interest from the following day
+    Daily,
+    /// Interest accrues each day on the balance alone, and earns interest once it is
+    /// paid at the end of the month
+    Monthly,
+}
+
+/// An annual interest rate that applies to the part of a balance above a threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RateTier {
+    threshold: Decimal,
+    rate: Decimal,
+}
+
+/// A day-by-day projection of a savings balance and the interest it earns. See
+/// [`project_interest`] for details.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InterestProjection {
+    currency: Currency,
+    balances: Vec<(Date<Utc>, Decimal)>,
+    payments: Vec<(Date<Utc>, Decimal)>,
+}
+
+impl SavingsInterest {
+    /// Create a new `SavingsInterest` at a flat annual `rate`, as a percentage (e.g. 4.5
+    /// for 4.5%).
+    pub fn new(rate: Decimal, compounding: SavingsCompounding) -> Self {
+        SavingsInterest::new_tiered(vec![RateTier::new(Decimal::ZERO, rate)], compounding)
+    }
+
+    /// Create a new `SavingsInterest` with tiered rates. Each tier's rate applies to the
+    /// part of the balance between its threshold and the next tier's threshold.
+    pub fn new_tiered(tiers: Vec<RateTier>, compounding: SavingsCompounding) -> Self {
+        SavingsInterest {
+            compounding,
+            tiers: sort_tiers(tiers),
+            changes: BTreeMap::new(),
+        }
+    }
+
+    /// Change the rates from the given date onwards.
+    pub fn set_rate(&mut self, date: Date<Utc>, tiers: Vec<RateTier>) {
+        self.changes.insert(date, sort_tiers(tiers));
+    }
+
+    /// How often accrued interest is added to the balance.
+    pub fn get_compounding(&self) -> SavingsCompounding {
+        self.compounding
+    }
+
+    /// The rate tiers on the given date, in threshold order.
+    pub fn get_tiers(&self, date: Date<Utc>) -> &[RateTier] {
+        self.changes
+            .range(..=date)
+            .next_back()
+            .map(|(_, tiers)| tiers)
+            .unwrap_or(&self.tiers)
+    }
+
+    /// The interest that the `balance` would earn over a year at the rates on the
+    /// given date.
+    pub fn get_annual_interest(&self, balance: Decimal, date: Date<Utc>) -> Decimal {
+        let tiers = self.get_tiers(date);
+        tiers
+            .iter()
+            .enumerate()
+            .map(|(i, tier)| {
+                let ceiling = tiers.get(i + 1).map(|t| t.threshold).unwrap_or(balance);
+                let portion = balance.min(ceiling) - tier.threshold;
+                portion.max(Decimal::ZERO) * tier.rate / Decimal::ONE_HUNDRED
+            })
+            .sum()
+    }
+}
+
+impl RateTier {
+    /// Create a new `RateTier` with an annual `rate`, as a percentage, for the part of a
+    /// balance above `threshold`.
+    pub fn new(threshold: Decimal, rate: Decimal) -> Self {
+        RateTier { threshold, rate }
+    }
+
+    /// The balance above which this tier's rate applies.
+    pub fn get_threshold(&self) -> Decimal {
+        self.threshold
+    }
+
+    /// The annual rate, as a percentage.
+    pub fn get_rate(&self) -> Decimal {
+        self.rate
+    }
+}
+
+impl InterestProjection {
+    /// The currency that every amount in this projection is in.
+    pub fn get_currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// The balance at the end of each day of the projection, including any interest
+    /// paid on that day, in date order.
+    pub fn get_balances(&self) -> &[(Date<Utc>, Decimal)] {
+        &self.balances
+    }
+
+    /// The interest paid at the end of each month, in date order.
+    pub fn get_payments(&self) -> Vec<(Date<Utc>, Money)> {
+        self.payments
+            .iter()
+            .map(|(date, amount)| (*date, Money::new(*amount, self.currency)))
+            .collect()
+    }
+
+    /// The total interest paid over the projection.
+    pub fn get_total_interest(&self) -> Money {
+        Money::new(
+            self.payments.iter().map(|(_, amount)| *amount).sum(),
+            self.currency,
+        )
+    }
+
+    /// Create revenue [`TransactionModel`]s for the interest payments, calculated from
+    /// the `clock`'s date, so that they can be included in affordability calculations.
+    pub fn to_models(&self, clock: &dyn Clock) -> Result<Vec<TransactionModel>, TransactionError> {
+        self.get_payments()
+            .into_iter()
+            .filter(|(_, amount)| amount.get_amount() > Decimal::ZERO)
+            .map(|(date, amount)| {
+                TransactionModel::new_with_clock(
+                    amount,
+                    None,
+                    Frequency::Once,
+                    date,
+                    Termination::Never,
+                    RoundingPolicy::Exact,
+                    clock,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Project a savings balance from `start` to `end` (inclusive), and the interest that it
+/// earns.
+///
+/// Each day's balance is the previous day's balance plus that day's contributions to the
+/// `savings` models. Interest accrues daily at 1/365th of the annual rate, and is paid
+/// into the balance on the last day of each month. Interest that has accrued since the
+/// last month end is not paid by the end of the projection.
+///
+/// Every model must be in the same currency as the `opening_balance`.
+pub fn project_interest(
+    opening_balance: Money,
+    start: Date<Utc>,
+    end: Date<Utc>,
+    savings: Option<&[TransactionModel]>,
+    interest: &SavingsInterest,
+) -> Result<InterestProjection, TransactionError> {
+    debug!(
+        "projecting interest on {} from {} to {}",
+        opening_balance, start, end
+    );
+
+    let currency = opening_balance.get_currency();
+    if let Some(c) = get_common_currency(savings, None, None)? {
+        opening_balance.check_currency(c)?;
+    }
+
+    // Savings are outflows from a budget, but inflows to the savings balance
+    let contributions = collect_contributions(savings, None, None);
+    let day_totals = acc_daily_contributions(&contributions, Some((start, end)));
+
+    let mut balance = opening_balance.get_amount();
+    let mut accrued = Decimal::ZERO;
+    let mut balances = Vec::new();
+    let mut payments = Vec::new();
+
+    for (date, total) in day_totals {
+        balance += total;
+
+        let earning = match interest.compounding {
+            SavingsCompounding::Daily => balance + accrued,
+            SavingsCompounding::Monthly => balance,
+        };
+        accrued += interest.get_annual_interest(earning, date) / dec!(365);
+
+        // Pay the accrued interest at the end of the month, carrying forward any
+        // fraction of the currency's minor unit
+        if date.succ().month() != date.month() {
+            let payment = currency.round(accrued);
+            trace!("{}: paying interest of {}", date, payment);
+            accrued -= payment;
+            balance += payment;
+            payments.push((date, payment));
+        }
+
+        balances.push((date, balance));
+    }
+
+    Ok(InterestProjection {
+        currency,
+        balances,
+        payments,
+    })
+}
+
+fn sort_tiers(mut tiers: Vec<RateTier>) -> Vec<RateTier> {
+    tiers.sort_by_key(|t| t.threshold);
+    tiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::TimeZone;
+
+    #[test]
+    fn annual_interest_tiered() {
+        let interest = SavingsInterest::new_tiered(
+            vec![
+                RateTier::new(dec!(5000), dec!(5)),
+                RateTier::new(Decimal::ZERO, dec!(1)),
+            ],
+            SavingsCompounding::Monthly,
+        );
+        let date = Utc.ymd(2000, 4, 1);
+
+        assert_eq!(interest.get_annual_interest(dec!(2000), date), dec!(20));
+        assert_eq!(interest.get_annual_interest(dec!(10000), date), dec!(300));
+        assert_eq!(interest.get_annual_interest(dec!(-100), date), dec!(0));
+    }
+
+    #[test]
+    fn project_interest_compounding() {
+        let project = |compounding| {
+            project_interest(
+                Money::aud(dec!(10000)),
+                Utc.ymd(2001, 4, 1),
+                Utc.ymd(2001, 5, 31),
+                None,
+                &SavingsInterest::new(dec!(5), compounding),
+            )
+            .unwrap()
+        };
+
+        // 30 days in April, then 31 days in May on the higher balance
+        let monthly = project(SavingsCompounding::Monthly);
+        assert_eq!(
+            monthly.get_payments(),
+            vec![
+                (Utc.ymd(2001, 4, 30), Money::aud(dec!(41.10))),
+                (Utc.ymd(2001, 5, 31), Money::aud(dec!(42.64))),
+            ]
+        );
+        assert_eq!(monthly.get_balances().last().unwrap().1, dec!(10083.74));
+
+        let daily = project(SavingsCompounding::Daily);
+        assert_eq!(
+            daily.get_payments()[0],
+            (Utc.ymd(2001, 4, 30), Money::aud(dec!(41.18)))
+        );
+        assert!(
+            daily.get_total_interest().get_amount() > monthly.get_total_interest().get_amount()
+        );
+    }
+
+    #[test]
+    fn project_interest_rate_change() {
+        let mut interest = SavingsInterest::new(dec!(3.65), SavingsCompounding::Monthly);
+        interest.set_rate(
+            Utc.ymd(2001, 4, 21),
+            vec![RateTier::new(Decimal::ZERO, dec!(7.3))],
+        );
+
+        // 20 days at 1c per day, then 10 days at 2c per day
+        let projection = project_interest(
+            Money::aud(dec!(100)),
+            Utc.ymd(2001, 4, 1),
+            Utc.ymd(2001, 4, 30),
+            None,
+            &interest,
+        )
+        .unwrap();
+        assert_eq!(projection.get_total_interest(), Money::aud(dec!(0.40)));
+    }
+
+    #[test]
+    fn project_interest_contributions() {
+        let clock = FixedClock::new(Utc.ymd(2001, 4, 1));
+        let savings = vec![TransactionModel::new_with_clock(
+            Money::aud(dec!(36.50)),
+            None,
+            Frequency::Daily(1),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap()];
+
+        // The balance grows by 36.50 a day, earning 1c a day for each 36.50 at 10%
+        let projection = project_interest(
+            Money::aud(dec!(0)),
+            Utc.ymd(2001, 4, 1),
+            Utc.ymd(2001, 4, 30),
+            Some(&savings),
+            &SavingsInterest::new(dec!(10), SavingsCompounding::Monthly),
+        )
+        .unwrap();
+        assert_eq!(projection.get_total_interest(), Money::aud(dec!(4.65)));
+        assert_eq!(projection.get_balances().last().unwrap().1, dec!(1099.65));
+
+        let models = projection.to_models(&clock).unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].get_value(), Money::aud(dec!(4.65)));
+        assert_eq!(models[0].get_end_date(), Some(Utc.ymd(2001, 4, 30)));
+    }
+
+    #[test]
+    fn project_interest_currency_mismatch() {
+        let clock = FixedClock::new(Utc.ymd(2001, 4, 1));
+        let savings = vec![TransactionModel::new_with_clock(
+            Money::new(dec!(10), Currency::USD),
+            None,
+            Frequency::Daily(1),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap()];
+
+        let result = project_interest(
+            Money::aud(dec!(0)),
+            clock.today(),
+            clock.today(),
+            Some(&savings),
+            &SavingsInterest::new(dec!(5), SavingsCompounding::Daily),
+        );
+        assert!(matches!(result, Err(TransactionError::Currency(_))));
+    }
+}
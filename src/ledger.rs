@@ -0,0 +1,429 @@
+use crate::{currency::CurrencyError, money::Money, transaction::TransactionModel};
+use chrono::{Date, Duration, Utc};
+use log::debug;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A payment that has actually been made, such as a bank debit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Payment {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date"))]
+    date: Date<Utc>,
+    amount: Money,
+}
+
+/// A record of the actual payments made for a [`TransactionModel`].
+///
+/// Use [`Ledger::reconcile`] to compare the recorded payments with the payments that the
+/// model expects.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ledger {
+    payments: Vec<Payment>,
+}
+
+/// An expected payment that has been matched to an actual [`Payment`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchedPayment {
+    expected_date: Date<Utc>,
+    expected_amount: Money,
+    payment: Payment,
+}
+
+/// The outcome of reconciling a single payment. See [`Ledger::reconcile`] for details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reconciliation {
+    /// No payment was recorded within the tolerance of the expected date
+    Missed(Date<Utc>, Money),
+    /// A payment was recorded for the expected date, though it may have been early, late
+    /// or for a different amount
+    Matched(MatchedPayment),
+    /// A payment was recorded that doesn't correspond to any expected payment
+    Unexpected(Payment),
+}
+
+/// The result of reconciling a [`Ledger`] against a [`TransactionModel`]. See
+/// [`Ledger::reconcile`] for details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    entries: Vec<Reconciliation>,
+}
+
+impl Payment {
+    pub fn new(date: Date<Utc>, amount: Money) -> Self {
+        Payment { date, amount }
+    }
+
+    pub fn get_date(&self) -> Date<Utc> {
+        self.date
+    }
+
+    pub fn get_amount(&self) -> Money {
+        self.amount
+    }
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Ledger::default()
+    }
+
+    /// Record an actual payment.
+    pub fn record(&mut self, payment: Payment) {
+        // Keep payments in date order, after any other payments on the same day
+        let i = self.payments.partition_point(|p| p.date <= payment.date);
+        self.payments.insert(i, payment);
+    }
+
+    /// Every recorded payment, in date order.
+    pub fn get_payments(&self) -> &[Payment] {
+        &self.payments
+    }
+
+    /// Reconcile the recorded payments against the payments that `model` expects from
+    /// `from` to `to` (inclusive).
+    ///
+    /// Each expected payment is matched to the nearest recorded payment within
+    /// `tolerance` days of it, with the closest pairs matched first. Matched payments
+    /// report whether they were early, late, over- or underpaid. Expected payments
+    /// without a match are reported as missed, and recorded payments between `from` and
+    /// `to` that weren't matched are reported as unexpected.
+    ///
+    /// Note that an expected payment is reported as missed even if its tolerance window
+    /// hasn't closed yet, so `to` should usually be no later than today, less the
+    /// tolerance.
+    ///
+    /// Every recorded payment must be in the same currency as the model.
+    pub fn reconcile(
+        &self,
+        model: &TransactionModel,
+        from: Date<Utc>,
+        to: Date<Utc>,
+        tolerance: Duration,
+    ) -> Result<ReconciliationReport, CurrencyError> {
+        debug!(
+            "reconciling {} payments from {} to {} with a tolerance of {} days",
+            self.payments.len(),
+            from,
+            to,
+            tolerance.num_days()
+        );
+
+        for payment in self.payments.iter() {
+            payment.amount.check_currency(model.get_currency())?;
+        }
+
+        let expected = model.get_payment_dates(from, to);
+        let payments: Vec<&Payment> = self
+            .payments
+            .iter()
+            .filter(|p| p.date >= from - tolerance && p.date <= to + tolerance)
+            .collect();
+
+        // Find every candidate pairing within the tolerance, then match the closest pairs
+        // first so that a payment is never claimed by a more distant expected date.
+        let mut candidates = Vec::new();
+        for (e, date) in expected.iter().enumerate() {
+            for (p, payment) in payments.iter().enumerate() {
+                let distance = (payment.date - *date).num_days().abs();
+                if distance <= tolerance.num_days() {
+                    candidates.push((distance, e, p));
+                }
+            }
+        }
+        candidates.sort_unstable();
+
+        let mut expected_matches = vec![None; expected.len()];
+        let mut payment_matched = vec![false; payments.len()];
+        for (_, e, p) in candidates {
+            if expected_matches[e].is_none() && !payment_matched[p] {
+                expected_matches[e] = Some(p);
+                payment_matched[p] = true;
+            }
+        }
+
+        let mut entries: Vec<Reconciliation> = expected
+            .iter()
+            .zip(expected_matches)
+            .map(|(date, matched)| {
+                let expected_amount = model.get_payment_amount(*date);
+                match matched {
+                    Some(p) => Reconciliation::Matched(MatchedPayment {
+                        expected_date: *date,
+                        expected_amount,
+                        payment: *payments[p],
+                    }),
+                    None => Reconciliation::Missed(*date, expected_amount),
+                }
+            })
+            .chain(
+                payments
+                    .iter()
+                    .zip(payment_matched)
+                    .filter(|(p, matched)| !matched && p.date >= from && p.date <= to)
+                    .map(|(p, _)| Reconciliation::Unexpected(**p)),
+            )
+            .collect();
+        entries.sort_by_key(|r| r.get_date());
+
+        Ok(ReconciliationReport { entries })
+    }
+}
+
+impl MatchedPayment {
+    /// The date the payment was expected.
+    pub fn get_expected_date(&self) -> Date<Utc> {
+        self.expected_date
+    }
+
+    /// The amount the payment was expected to be.
+    pub fn get_expected_amount(&self) -> Money {
+        self.expected_amount
+    }
+
+    /// The actual payment.
+    pub fn get_payment(&self) -> Payment {
+        self.payment
+    }
+
+    /// The number of days after the expected date that the payment was made. Early
+    /// payments have a negative number of days.
+    pub fn get_days_late(&self) -> i64 {
+        (self.payment.date - self.expected_date).num_days()
+    }
+
+    /// The amount paid over the expected amount. Underpayments have a negative
+    /// difference.
+    pub fn get_difference(&self) -> Decimal {
+        self.payment.amount.get_amount() - self.expected_amount.get_amount()
+    }
+
+    pub fn is_early(&self) -> bool {
+        self.get_days_late() < 0
+    }
+
+    pub fn is_late(&self) -> bool {
+        self.get_days_late() > 0
+    }
+
+    pub fn is_overpaid(&self) -> bool {
+        self.get_difference() > Decimal::ZERO
+    }
+
+    pub fn is_underpaid(&self) -> bool {
+        self.get_difference() < Decimal::ZERO
+    }
+}
+
+impl Reconciliation {
+    /// The expected date of a missed or matched payment, or the date of an unexpected
+    /// payment.
+    pub fn get_date(&self) -> Date<Utc> {
+        match self {
+            Reconciliation::Missed(date, _) => *date,
+            Reconciliation::Matched(m) => m.expected_date,
+            Reconciliation::Unexpected(p) => p.date,
+        }
+    }
+}
+
+impl ReconciliationReport {
+    /// Every reconciled payment, in date order.
+    pub fn get_entries(&self) -> &[Reconciliation] {
+        &self.entries
+    }
+
+    /// The dates of expected payments that weren't made.
+    pub fn get_missed(&self) -> Vec<Date<Utc>> {
+        self.entries
+            .iter()
+            .filter_map(|r| match r {
+                Reconciliation::Missed(date, _) => Some(*date),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Recorded payments that don't correspond to any expected payment.
+    pub fn get_unexpected(&self) -> Vec<Payment> {
+        self.entries
+            .iter()
+            .filter_map(|r| match r {
+                Reconciliation::Unexpected(p) => Some(*p),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every expected payment that was matched to a recorded payment.
+    pub fn get_matched(&self) -> Vec<MatchedPayment> {
+        self.entries
+            .iter()
+            .filter_map(|r| match r {
+                Reconciliation::Matched(m) => Some(*m),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_early(&self) -> Vec<MatchedPayment> {
+        self.filter_matched(MatchedPayment::is_early)
+    }
+
+    pub fn get_late(&self) -> Vec<MatchedPayment> {
+        self.filter_matched(MatchedPayment::is_late)
+    }
+
+    pub fn get_overpaid(&self) -> Vec<MatchedPayment> {
+        self.filter_matched(MatchedPayment::is_overpaid)
+    }
+
+    pub fn get_underpaid(&self) -> Vec<MatchedPayment> {
+        self.filter_matched(MatchedPayment::is_underpaid)
+    }
+
+    /// Whether every expected payment was made on time and for the expected amount,
+    /// with no unexpected payments.
+    pub fn is_reconciled(&self) -> bool {
+        self.entries.iter().all(|r| match r {
+            Reconciliation::Matched(m) => {
+                m.get_days_late() == 0 && !m.is_overpaid() && !m.is_underpaid()
+            }
+            _ => false,
+        })
+    }
+
+    fn filter_matched(&self, f: fn(&MatchedPayment) -> bool) -> Vec<MatchedPayment> {
+        self.get_matched().into_iter().filter(f).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::FixedClock, currency::Currency, frequency::Frequency, rounding::RoundingPolicy,
+        transaction::Termination,
+    };
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn rent() -> TransactionModel {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        TransactionModel::new_with_clock(
+            Money::new(dec!(100), Currency::AUD),
+            None,
+            Frequency::MonthlyDate(1, vec![1]),
+            Utc.ymd(2000, 4, 1),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reconcile_ledger() {
+        let mut ledger = Ledger::new();
+        ledger.record(Payment::new(Utc.ymd(2000, 5, 3), Money::aud(dec!(100))));
+        ledger.record(Payment::new(Utc.ymd(2000, 4, 1), Money::aud(dec!(100))));
+        ledger.record(Payment::new(Utc.ymd(2000, 6, 29), Money::aud(dec!(90))));
+        ledger.record(Payment::new(Utc.ymd(2000, 7, 20), Money::aud(dec!(15))));
+
+        let report = ledger
+            .reconcile(
+                &rent(),
+                Utc.ymd(2000, 4, 1),
+                Utc.ymd(2000, 8, 31),
+                Duration::days(3),
+            )
+            .unwrap();
+
+        assert_eq!(report.get_entries().len(), 6);
+        assert!(!report.is_reconciled());
+        assert_eq!(report.get_matched().len(), 3);
+        assert_eq!(
+            report.get_missed(),
+            vec![Utc.ymd(2000, 6, 1), Utc.ymd(2000, 8, 1)]
+        );
+        assert_eq!(
+            report.get_unexpected(),
+            vec![Payment::new(Utc.ymd(2000, 7, 20), Money::aud(dec!(15)))]
+        );
+
+        let late = report.get_late();
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].get_expected_date(), Utc.ymd(2000, 5, 1));
+        assert_eq!(late[0].get_days_late(), 2);
+
+        let early = report.get_early();
+        assert_eq!(early.len(), 1);
+        assert_eq!(early[0].get_expected_date(), Utc.ymd(2000, 7, 1));
+        assert_eq!(early[0].get_days_late(), -2);
+
+        let underpaid = report.get_underpaid();
+        assert_eq!(underpaid, early);
+        assert_eq!(underpaid[0].get_difference(), dec!(-10));
+        assert!(report.get_overpaid().is_empty());
+    }
+
+    #[test]
+    fn reconcile_closest_match() {
+        // Both payments are within tolerance of 1 May, but the second is closer to 1 June
+        let mut ledger = Ledger::new();
+        ledger.record(Payment::new(Utc.ymd(2000, 5, 1), Money::aud(dec!(110))));
+        ledger.record(Payment::new(Utc.ymd(2000, 5, 20), Money::aud(dec!(100))));
+
+        let report = ledger
+            .reconcile(
+                &rent(),
+                Utc.ymd(2000, 5, 1),
+                Utc.ymd(2000, 6, 1),
+                Duration::days(20),
+            )
+            .unwrap();
+
+        let matched = report.get_matched();
+        assert_eq!(matched.len(), 2);
+        assert_eq!(matched[0].get_payment().get_date(), Utc.ymd(2000, 5, 1));
+        assert!(matched[0].is_overpaid());
+        assert_eq!(matched[1].get_days_late(), -12);
+    }
+
+    #[test]
+    fn reconcile_reconciled() {
+        let mut ledger = Ledger::new();
+        ledger.record(Payment::new(Utc.ymd(2000, 4, 1), Money::aud(dec!(100))));
+
+        let report = ledger
+            .reconcile(
+                &rent(),
+                Utc.ymd(2000, 4, 1),
+                Utc.ymd(2000, 4, 30),
+                Duration::days(3),
+            )
+            .unwrap();
+        assert!(report.is_reconciled());
+    }
+
+    #[test]
+    fn reconcile_mixed_currencies() {
+        let mut ledger = Ledger::new();
+        ledger.record(Payment::new(
+            Utc.ymd(2000, 4, 1),
+            Money::new(dec!(100), Currency::USD),
+        ));
+
+        assert_eq!(
+            ledger.reconcile(
+                &rent(),
+                Utc.ymd(2000, 4, 1),
+                Utc.ymd(2000, 4, 30),
+                Duration::days(3),
+            ),
+            Err(CurrencyError::Mismatch(Currency::USD, Currency::AUD))
+        );
+    }
+}
@@ -1,11 +1,51 @@
+mod account;
+mod amounts;
+mod balance;
+mod business_day;
+mod clock;
 mod contribution;
+mod currency;
+mod escalation;
+mod exchange;
 mod frequency;
+mod goal;
+mod holiday;
+mod interest;
+mod ledger;
+mod loan;
+mod money;
+mod payoff;
+mod resolve;
+mod rounding;
+mod rrule;
+#[cfg(feature = "serde")]
+mod serialize;
 mod transaction;
 
-pub use contribution::ContributionError;
+pub use account::{Account, AccountError, AccountId, Accounts, Transfer};
+pub use amounts::AmountSchedule;
+pub use balance::{project_balance, BalanceProjection};
+pub use business_day::BusinessDayConvention;
+pub use clock::{Clock, FixedClock, SteppingClock, SystemClock};
+pub use contribution::{Contribution, ContributionError};
+pub use currency::{Currency, CurrencyError};
+pub use escalation::{Escalation, IndexTable, IndexTableError};
+pub use exchange::{ExchangeError, ExchangeRates, RateTable, RateTableError};
 pub use frequency::{Frequency, FrequencyMonthDay};
-pub use transaction::{is_affordable, AffordabilityResult, TransactionError, TransactionModel};
-
-// This represents the number of decimal places that a currency can validly express.
-// @todo Support the full range of currency precisions specified in ISO 4217.
-const CURRENCY_PRECISION: u32 = 2;
+pub use goal::{SavingsGoal, SavingsGoalError};
+pub use holiday::{Holiday, HolidayCalendar, HolidayError, HolidayRule};
+pub use interest::{
+    project_interest, InterestProjection, RateTier, SavingsCompounding, SavingsInterest,
+};
+pub use ledger::{Ledger, MatchedPayment, Payment, Reconciliation, ReconciliationReport};
+pub use loan::{AmortisationRow, Compounding, Loan, LoanError, LoanTerm};
+pub use money::Money;
+pub use payoff::{plan_payoff, DebtPayoff, PayoffError, PayoffPlan, PayoffStrategy};
+pub use resolve::{resolve_deficit, Amelioration, ModelRef, Resolution, ResolveError};
+pub use rounding::RoundingPolicy;
+pub use rrule::RRuleError;
+pub use transaction::{
+    affordability_report, affordability_report_in, is_affordable, is_affordable_in,
+    AffordabilityReport, AffordabilityResult, ScheduledContribution, Termination, TransactionError,
+    TransactionModel,
+};
@@ -0,0 +1,722 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    amounts::AmountSchedule,
+    clock::Clock,
+    currency::{Currency, CurrencyError},
+    escalation::add_years,
+    frequency::Frequency,
+    money::Money,
+    rounding::RoundingPolicy,
+    transaction::{Termination, TransactionError, TransactionModel},
+};
+use chrono::{Date, Duration, Utc};
+use log::{debug, trace};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A loan that is repaid with interest, such as a mortgage or car loan.
+///
+/// A `Loan` generates an amortisation table that splits each repayment into interest and
+/// principal, and the expense [`TransactionModel`]s that make those repayments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Loan {
+    principal: Money,
+    rate: Decimal,
+    compounding: Compounding,
+    frequency: Frequency,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date"))]
+    start_date: Date<Utc>,
+    repayment: Money,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date_map"))]
+    extra_repayments: BTreeMap<Date<Utc>, Decimal>,
+}
+
+/// How interest is calculated and added to a [`Loan`]'s balance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Compounding {
+    /// The annual rate is divided evenly between the repayments in a year, and interest
+    /// is added on each repayment date. A first period that is shorter than the others
+    /// is charged pro rata.
+    Repayment,
+    /// Interest compounds every day, at 1/365th of the annual rate
+    Daily,
+    /// Interest accrues every day without compounding, at 1/365th of the annual rate, and
+    /// is added on each repayment date
+    Simple,
+}
+
+/// How long a [`Loan`] is repaid over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoanTerm {
+    /// The loan is repaid in `n` repayments
+    Count(u32),
+    /// The loan is repaid by the given date (inclusive)
+    EndDate(Date<Utc>),
+    /// The loan is repaid with a fixed repayment amount, for as long as it takes
+    Repayment(Money),
+}
+
+/// A single row of a [`Loan`]'s amortisation table. See [`Loan::amortisation_table`]
+/// for details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AmortisationRow {
+    date: Date<Utc>,
+    interest: Money,
+    principal: Money,
+    extra: Money,
+    balance: Money,
+}
+
+/// Errors encountered whilst working with [`Loan`]s.
+#[derive(Error, Debug, PartialEq)]
+pub enum LoanError {
+    #[error(transparent)]
+    Currency(#[from] CurrencyError),
+    #[error("could not create repayment model: {0}")]
+    Transaction(#[from] TransactionError),
+    #[error("the interest rate cannot be negative: {0}")]
+    NegativeRate(Decimal),
+    #[error("a repayment of {0} does not cover the interest on the loan")]
+    NegativeAmortisation(Money),
+    #[error("the loan has no repayments")]
+    NoRepayments,
+    #[error("the loan has no repayments before {0} to repay the remaining balance")]
+    Unpaid(Date<Utc>),
+    #[error("extra repayments must be made after the loan starts")]
+    ExtraRepaymentDate(Date<Utc>),
+}
+
+impl Loan {
+    /// Create a new `Loan` of `principal` from the `start_date`, repaid at the given
+    /// `frequency` from the first repayment date after the `start_date`.
+    ///
+    /// The `rate` is an annual percentage (e.g. 6.5 for 6.5%). If the `term` is a number
+    /// of repayments or an end date, the repayment amount is the smallest amount (in the
+    /// currency's minor unit) that repays the loan within that term. The final
+    /// repayment is usually smaller than the others.
+    pub fn new(
+        principal: Money,
+        rate: Decimal,
+        compounding: Compounding,
+        frequency: Frequency,
+        start_date: Date<Utc>,
+        term: LoanTerm,
+    ) -> Result<Self, LoanError> {
+        if !principal.is_valid() {
+            return Err(TransactionError::CurrencyPrecision(principal).into());
+        }
+        if rate < Decimal::ZERO {
+            return Err(LoanError::NegativeRate(rate));
+        }
+
+        let mut loan = Loan {
+            principal,
+            rate,
+            compounding,
+            frequency,
+            start_date,
+            repayment: Money::zero(principal.get_currency()),
+            extra_repayments: BTreeMap::new(),
+        };
+
+        loan.repayment = match term {
+            LoanTerm::Repayment(repayment) => {
+                repayment.check_currency(principal.get_currency())?;
+                if !repayment.is_valid() {
+                    return Err(TransactionError::CurrencyPrecision(repayment).into());
+                }
+                repayment
+            }
+            LoanTerm::Count(n) => loan.solve_repayment(n)?,
+            LoanTerm::EndDate(date) => {
+                let n = loan
+                    .get_repayment_dates()
+                    .take_while(|d| *d <= date)
+                    .count();
+                loan.solve_repayment(n as u32)?
+            }
+        };
+
+        // Check that the repayments are enough to pay off the loan
+        loan.simulate(loan.repayment.get_amount(), None)?;
+
+        Ok(loan)
+    }
+
+    /// Make an extra repayment on the given date, which reduces the balance and
+    /// therefore the interest charged from that date.
+    pub fn add_extra_repayment(&mut self, date: Date<Utc>, amount: Money) -> Result<(), LoanError> {
+        amount.check_currency(self.get_currency())?;
+        if !amount.is_valid() {
+            return Err(TransactionError::CurrencyPrecision(amount).into());
+        }
+        if date <= self.start_date {
+            return Err(LoanError::ExtraRepaymentDate(date));
+        }
+
+        *self.extra_repayments.entry(date).or_insert(Decimal::ZERO) += amount.get_amount();
+        Ok(())
+    }
+
+    /// The amount borrowed.
+    pub fn get_principal(&self) -> Money {
+        self.principal
+    }
+
+    /// The currency of this loan.
+    pub fn get_currency(&self) -> Currency {
+        self.principal.get_currency()
+    }
+
+    /// The annual interest rate, as a percentage.
+    pub fn get_rate(&self) -> Decimal {
+        self.rate
+    }
+
+    /// How interest is calculated.
+    pub fn get_compounding(&self) -> Compounding {
+        self.compounding
+    }
+
+    /// How often repayments are made.
+    pub fn get_frequency(&self) -> &Frequency {
+        &self.frequency
+    }
+
+    /// The date the loan is drawn down, from which interest accrues.
+    pub fn get_start_date(&self) -> Date<Utc> {
+        self.start_date
+    }
+
+    /// The amount of each regular repayment.
+    pub fn get_repayment(&self) -> Money {
+        self.repayment
+    }
+
+    /// The extra repayments that have been made, in date order.
+    pub fn get_extra_repayments(&self) -> Vec<(Date<Utc>, Money)> {
+        self.extra_repayments
+            .iter()
+            .map(|(date, amount)| (*date, Money::new(*amount, self.get_currency())))
+            .collect()
+    }
+
+    /// The amortisation table for this loan, with a row for every repayment until the
+    /// loan is repaid.
+    ///
+    /// Each row splits the repayment into interest and principal. Extra repayments are
+    /// included in the row for their date, which is a row of its own if the date isn't a
+    /// repayment date.
+    pub fn amortisation_table(&self) -> Vec<AmortisationRow> {
+        // The repayment was checked when the loan was created, and extra repayments can
+        // only reduce the balance.
+        self.simulate(self.repayment.get_amount(), None)
+            .expect("loan repayments cover the balance")
+    }
+
+    /// The balance at the end of the given date, after any repayments on that date.
+    pub fn get_balance(&self, date: Date<Utc>) -> Money {
+        self.amortisation_table()
+            .iter()
+            .take_while(|row| row.date <= date)
+            .last()
+            .map(|row| row.balance)
+            .unwrap_or(self.principal)
+    }
+
+    /// The date of the final repayment.
+    pub fn get_payoff_date(&self) -> Date<Utc> {
+        self.amortisation_table()
+            .last()
+            .map(|row| row.date)
+            .unwrap_or(self.start_date)
+    }
+
+    /// The total interest charged over the life of the loan.
+    pub fn get_total_interest(&self) -> Money {
+        Money::new(
+            self.amortisation_table()
+                .iter()
+                .map(|row| row.interest.get_amount())
+                .sum(),
+            self.get_currency(),
+        )
+    }
+
+    /// Create the expense [`TransactionModel`]s that repay this loan, calculated from the
+    /// `clock`'s date.
+    ///
+    /// The first model makes the regular repayments, and each extra repayment is a
+    /// model of its own. Only the repayments from the `clock`'s date onwards are
+    /// included, so a loan that is already being repaid can be modelled too.
+    pub fn to_models(&self, clock: &dyn Clock) -> Result<Vec<TransactionModel>, LoanError> {
+        let start_date = clock.today().max(self.start_date.succ());
+        let table: Vec<AmortisationRow> = self
+            .amortisation_table()
+            .into_iter()
+            .filter(|row| row.date >= start_date)
+            .collect();
+        let repayments: BTreeMap<Date<Utc>, Decimal> = table
+            .iter()
+            .map(|row| (row.date, row.get_payment().get_amount()))
+            .filter(|(_, payment)| *payment > Decimal::ZERO)
+            .collect();
+
+        let mut models = Vec::new();
+        if let Some(last) = repayments.keys().next_back().copied() {
+            models.push(TransactionModel::new_variable(
+                self.repayment,
+                AmountSchedule::Dates(repayments),
+                self.frequency.clone(),
+                start_date,
+                Termination::EndDate(last),
+                RoundingPolicy::Exact,
+                clock,
+            )?);
+        }
+
+        for row in table
+            .iter()
+            .filter(|row| row.extra.get_amount() > Decimal::ZERO)
+        {
+            models.push(TransactionModel::new_with_clock(
+                row.extra,
+                None,
+                Frequency::Once,
+                row.date,
+                Termination::Never,
+                RoundingPolicy::Exact,
+                clock,
+            )?);
+        }
+
+        Ok(models)
+    }
+
+    // Get the date of every repayment, which start after the loan is drawn down
+    fn get_repayment_dates(&self) -> impl Iterator<Item = Date<Utc>> + '_ {
+        self.frequency.occurrences(self.start_date.succ())
+    }
+
+    // Find the smallest repayment that repays the loan in `n` repayments
+    fn solve_repayment(&self, n: u32) -> Result<Money, LoanError> {
+        if n == 0 {
+            return Err(LoanError::NoRepayments);
+        }
+        if self.get_repayment_dates().nth(n as usize - 1).is_none() {
+            return Err(TransactionError::PaymentCount(n).into());
+        }
+
+        let currency = self.get_currency();
+        let unit = currency.get_minor_unit();
+        let repays = |repayment: Decimal| {
+            self.simulate(repayment, Some(n))
+                .map(|table| table.last().map(|r| r.balance.get_amount()) == Some(Decimal::ZERO))
+                .unwrap_or(false)
+        };
+
+        // Find an upper bound, then narrow it down to the nearest minor unit
+        let mut low = Decimal::ZERO;
+        let mut high = self.principal.get_amount().max(unit);
+        while !repays(high) {
+            low = high;
+            high *= dec!(2);
+        }
+        while high - low > unit {
+            let mid = low + ((high - low) / unit / dec!(2)).floor() * unit;
+            if repays(mid) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        debug!("solved repayment of {} over {} repayments", high, n);
+        Ok(Money::new(high, currency))
+    }
+
+    // Calculate the amortisation table for the given repayment amount, stopping after
+    // `limit` repayments if provided. Extra repayments are ignored when there is a limit,
+    // as they aren't part of the loan's term.
+    fn simulate(
+        &self,
+        repayment: Decimal,
+        limit: Option<u32>,
+    ) -> Result<Vec<AmortisationRow>, LoanError> {
+        let currency = self.get_currency();
+        let rate = self.rate / Decimal::ONE_HUNDRED;
+        let periodic_rate = rate / Decimal::from(self.get_repayments_per_year().max(1));
+        let no_extras = BTreeMap::new();
+        let extras = match limit {
+            Some(_) => &no_extras,
+            None => &self.extra_repayments,
+        };
+        let mut extras = extras.iter().peekable();
+
+        // A short first period, such as a loan drawn the day before its first
+        // repayment, is charged in proportion to a regular period rather than a whole
+        // period's interest. A loan drawn on a scheduled repayment date has a full first
+        // period.
+        let mut repayments = self.get_repayment_dates();
+        let (first, second) = (repayments.next(), repayments.next());
+        let mut scheduled = self.frequency.occurrences(self.start_date);
+        let is_full_period = scheduled.next() == Some(self.start_date) && scheduled.next() == first;
+        let regular_period = match (first, second) {
+            (Some(first), Some(second)) if !is_full_period => second - first,
+            _ => Duration::zero(),
+        };
+
+        let mut repayments = self.get_repayment_dates();
+        let mut next_repayment = repayments.next();
+        let mut period_start = self.start_date;
+        let mut last = self.start_date;
+        let mut balance = self.principal.get_amount();
+        let mut accrued = Decimal::ZERO;
+        let mut count = 0;
+        let mut table = Vec::new();
+
+        while balance > Decimal::ZERO && limit.is_none_or(|n| count < n) {
+            let repayment_date = next_repayment.ok_or(LoanError::Unpaid(last))?;
+            let (date, is_repayment) = match extras.peek() {
+                Some((d, _)) if **d < repayment_date => (**d, false),
+                _ => (repayment_date, true),
+            };
+
+            // Accrue interest up to this date
+            let days = Decimal::from((date - last).num_days());
+            accrued += match self.compounding {
+                Compounding::Repayment => {
+                    let mut period = repayment_date - period_start;
+                    if period_start == self.start_date {
+                        period = period.max(regular_period);
+                    }
+                    balance * periodic_rate * days / Decimal::from(period.num_days())
+                }
+                Compounding::Daily => {
+                    let daily = Decimal::ONE + rate / dec!(365);
+                    let factor = (0..(date - last).num_days()).fold(Decimal::ONE, |f, _| f * daily);
+                    (balance + accrued) * (factor - Decimal::ONE)
+                }
+                Compounding::Simple => balance * rate * days / dec!(365),
+            };
+            last = date;
+
+            let mut interest = Decimal::ZERO;
+            let mut principal = Decimal::ZERO;
+            if is_repayment {
+                interest = currency.round(accrued);
+                accrued = Decimal::ZERO;
+                balance += interest;
+
+                let payment = repayment.min(balance);
+                if payment <= interest && payment < balance {
+                    return Err(LoanError::NegativeAmortisation(Money::new(
+                        repayment, currency,
+                    )));
+                }
+                balance -= payment;
+                principal = payment - interest;
+
+                period_start = date;
+                next_repayment = repayments.next();
+                count += 1;
+            }
+
+            let mut extra = Decimal::ZERO;
+            while let Some((_, amount)) = extras.next_if(|(d, _)| **d <= date) {
+                let amount = (*amount).min(balance);
+                balance -= amount;
+                extra += amount;
+            }
+
+            trace!(
+                "{}: interest {}, principal {}, extra {}, balance {}",
+                date,
+                interest,
+                principal,
+                extra,
+                balance
+            );
+
+            table.push(AmortisationRow {
+                date,
+                interest: Money::new(interest, currency),
+                principal: Money::new(principal, currency),
+                extra: Money::new(extra, currency),
+                balance: Money::new(balance, currency),
+            });
+        }
+
+        Ok(table)
+    }
+
+    // Get the number of scheduled repayments in a year, counted over the year from the
+    // loan's start date (exclusive of the same date a year later)
+    fn get_repayments_per_year(&self) -> usize {
+        match add_years(self.start_date, 1) {
+            Some(year_end) => self
+                .frequency
+                .occurrences(self.start_date)
+                .take_while(|d| *d < year_end)
+                .count(),
+            None => 1,
+        }
+    }
+}
+
+impl AmortisationRow {
+    /// The date of this repayment.
+    pub fn get_date(&self) -> Date<Utc> {
+        self.date
+    }
+
+    /// The regular repayment, which is the sum of the interest and principal.
+    pub fn get_payment(&self) -> Money {
+        Money::new(
+            self.interest.get_amount() + self.principal.get_amount(),
+            self.interest.get_currency(),
+        )
+    }
+
+    /// The interest charged since the previous repayment.
+    pub fn get_interest(&self) -> Money {
+        self.interest
+    }
+
+    /// The part of the regular repayment that reduces the balance.
+    pub fn get_principal(&self) -> Money {
+        self.principal
+    }
+
+    /// The extra repayments made on this date.
+    pub fn get_extra(&self) -> Money {
+        self.extra
+    }
+
+    /// The balance after this repayment.
+    pub fn get_balance(&self) -> Money {
+        self.balance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::TimeZone;
+
+    fn car_loan() -> Loan {
+        Loan::new(
+            Money::aud(dec!(10000)),
+            dec!(12),
+            Compounding::Repayment,
+            Frequency::MonthlyDate(1, vec![1]),
+            Utc.ymd(2000, 1, 1),
+            LoanTerm::Count(12),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn loan_solve_repayment() {
+        let loan = car_loan();
+
+        // The textbook annuity repayment is 888.49
+        assert_eq!(loan.get_repayment(), Money::aud(dec!(888.49)));
+
+        let table = loan.amortisation_table();
+        assert_eq!(table.len(), 12);
+        assert_eq!(table[0].get_date(), Utc.ymd(2000, 2, 1));
+        assert_eq!(table[0].get_interest(), Money::aud(dec!(100)));
+        assert_eq!(table[0].get_principal(), Money::aud(dec!(788.49)));
+        assert_eq!(table[11].get_balance(), Money::aud(dec!(0)));
+        assert!(table[11].get_payment().get_amount() <= dec!(888.49));
+        assert_eq!(loan.get_payoff_date(), Utc.ymd(2001, 1, 1));
+
+        let principal: Decimal = table.iter().map(|r| r.get_principal().get_amount()).sum();
+        assert_eq!(principal, dec!(10000));
+    }
+
+    #[test]
+    fn loan_fixed_repayment() {
+        let loan = Loan::new(
+            Money::aud(dec!(1000)),
+            dec!(0),
+            Compounding::Simple,
+            Frequency::Weekly(1, vec![1]),
+            Utc.ymd(2000, 1, 1),
+            LoanTerm::Repayment(Money::aud(dec!(300))),
+        )
+        .unwrap();
+
+        let table = loan.amortisation_table();
+        assert_eq!(table.len(), 4);
+        assert_eq!(table[3].get_payment(), Money::aud(dec!(100)));
+        assert_eq!(loan.get_total_interest(), Money::aud(dec!(0)));
+
+        let result = Loan::new(
+            Money::aud(dec!(10000)),
+            dec!(12),
+            Compounding::Repayment,
+            Frequency::MonthlyDate(1, vec![1]),
+            Utc.ymd(2000, 1, 1),
+            LoanTerm::Repayment(Money::aud(dec!(100))),
+        );
+        assert_eq!(
+            result,
+            Err(LoanError::NegativeAmortisation(Money::aud(dec!(100))))
+        );
+    }
+
+    #[test]
+    fn loan_compounding() {
+        let interest = |compounding| {
+            Loan::new(
+                Money::aud(dec!(10000)),
+                dec!(12),
+                compounding,
+                Frequency::MonthlyDate(1, vec![1]),
+                Utc.ymd(2001, 1, 1),
+                LoanTerm::Count(12),
+            )
+            .unwrap()
+            .amortisation_table()[0]
+                .get_interest()
+        };
+
+        // 31 days of interest in January
+        assert_eq!(interest(Compounding::Repayment), Money::aud(dec!(100)));
+        assert_eq!(interest(Compounding::Simple), Money::aud(dec!(101.92)));
+        assert_eq!(interest(Compounding::Daily), Money::aud(dec!(102.42)));
+    }
+
+    #[test]
+    fn loan_compounding_short_first_period() {
+        let loan = Loan::new(
+            Money::aud(dec!(10000)),
+            dec!(12),
+            Compounding::Repayment,
+            Frequency::MonthlyDate(1, vec![1]),
+            Utc.ymd(2000, 1, 31),
+            LoanTerm::Count(12),
+        )
+        .unwrap();
+
+        // 1 day of interest, in proportion to the 29 days from 1 February to 1 March
+        let table = loan.amortisation_table();
+        assert_eq!(table[0].get_date(), Utc.ymd(2000, 2, 1));
+        assert_eq!(table[0].get_interest(), Money::aud(dec!(3.45)));
+        assert_eq!(table[1].get_interest(), Money::aud(dec!(91.23)));
+    }
+
+    #[test]
+    fn loan_repayments_per_year() {
+        // 2000-01-01 is a Saturday, and 2001-01-01 is the 53rd Monday after it
+        let loan = Loan::new(
+            Money::aud(dec!(10000)),
+            dec!(12),
+            Compounding::Repayment,
+            Frequency::Weekly(1, vec![1]),
+            Utc.ymd(2000, 1, 1),
+            LoanTerm::Count(52),
+        )
+        .unwrap();
+        assert_eq!(loan.get_repayments_per_year(), 52);
+        assert_eq!(car_loan().get_repayments_per_year(), 12);
+    }
+
+    #[test]
+    fn loan_compounding_full_first_period() {
+        let loan = Loan::new(
+            Money::aud(dec!(10000)),
+            dec!(12),
+            Compounding::Repayment,
+            Frequency::MonthlyDate(1, vec![1]),
+            Utc.ymd(2001, 2, 1),
+            LoanTerm::Count(12),
+        )
+        .unwrap();
+
+        // Drawn on a repayment date, so February is a whole period despite being shorter
+        // than March
+        let table = loan.amortisation_table();
+        assert_eq!(table[0].get_date(), Utc.ymd(2001, 3, 1));
+        assert_eq!(table[0].get_interest(), Money::aud(dec!(100)));
+    }
+
+    #[test]
+    fn loan_extra_repayments() {
+        let mut loan = car_loan();
+        let interest = loan.get_total_interest();
+
+        loan.add_extra_repayment(Utc.ymd(2000, 3, 15), Money::aud(dec!(2000)))
+            .unwrap();
+        assert!(loan.get_payoff_date() < Utc.ymd(2001, 1, 1));
+        assert!(loan.get_total_interest().get_amount() < interest.get_amount());
+
+        let table = loan.amortisation_table();
+        assert_eq!(table[2].get_date(), Utc.ymd(2000, 3, 15));
+        assert_eq!(table[2].get_extra(), Money::aud(dec!(2000)));
+        assert_eq!(table[2].get_payment(), Money::aud(dec!(0)));
+        assert_eq!(
+            loan.get_balance(Utc.ymd(2000, 3, 15)),
+            table[2].get_balance()
+        );
+        assert_eq!(
+            loan.get_balance(Utc.ymd(2000, 1, 15)),
+            Money::aud(dec!(10000))
+        );
+
+        assert_eq!(
+            loan.add_extra_repayment(Utc.ymd(2000, 1, 1), Money::aud(dec!(10))),
+            Err(LoanError::ExtraRepaymentDate(Utc.ymd(2000, 1, 1)))
+        );
+    }
+
+    #[test]
+    fn loan_to_models() {
+        let mut loan = car_loan();
+        loan.add_extra_repayment(Utc.ymd(2000, 3, 15), Money::aud(dec!(2000)))
+            .unwrap();
+        let clock = FixedClock::new(Utc.ymd(2000, 1, 1));
+        let models = loan.to_models(&clock).unwrap();
+
+        assert_eq!(models.len(), 2);
+        let table = loan.amortisation_table();
+        let last = table.last().unwrap();
+        assert_eq!(models[0].get_end_date(), Some(last.get_date()));
+        assert_eq!(
+            models[0].get_payment_amount(last.get_date()),
+            last.get_payment()
+        );
+        assert_eq!(
+            models[0].get_payment_amount(Utc.ymd(2000, 2, 1)),
+            Money::aud(dec!(888.49))
+        );
+        assert_eq!(models[1].get_value(), Money::aud(dec!(2000)));
+    }
+
+    #[test]
+    fn loan_to_models_running() {
+        let mut loan = car_loan();
+        loan.add_extra_repayment(Utc.ymd(2000, 3, 15), Money::aud(dec!(2000)))
+            .unwrap();
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let models = loan.to_models(&clock).unwrap();
+
+        // Past repayments, including the extra repayment, aren't modelled
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].get_payment_start_date(), clock.today());
+        assert_eq!(
+            models[0].get_payment_dates(clock.today(), loan.get_payoff_date())[0],
+            clock.today()
+        );
+        assert_eq!(models[0].get_end_date(), Some(loan.get_payoff_date()));
+    }
+}
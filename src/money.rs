@@ -0,0 +1,124 @@
+use std::fmt::{Display, Error, Formatter};
+
+use crate::currency::{Currency, CurrencyError};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An amount of money in a given [`Currency`].
+///
+/// Amounts in different currencies can't be combined without converting them first, so
+/// arithmetic between two `Money` values fails if their currencies differ.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Money { amount, currency }
+    }
+
+    /// A zero amount in the given currency.
+    pub fn zero(currency: Currency) -> Self {
+        Money::new(Decimal::ZERO, currency)
+    }
+
+    pub fn get_amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn get_currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Add two amounts, which must be in the same currency.
+    pub fn checked_add(self, other: Money) -> Result<Money, CurrencyError> {
+        self.check_currency(other.currency)?;
+        Ok(Money::new(self.amount + other.amount, self.currency))
+    }
+
+    /// Subtract `other` from this amount. Both amounts must be in the same currency.
+    pub fn checked_sub(self, other: Money) -> Result<Money, CurrencyError> {
+        self.check_currency(other.currency)?;
+        Ok(Money::new(self.amount - other.amount, self.currency))
+    }
+
+    /// Whether this amount can be expressed in its currency's minor unit.
+    pub fn is_valid(&self) -> bool {
+        self.currency.is_valid_amount(self.amount)
+    }
+
+    /// Round this amount to its currency's minor unit.
+    pub fn round(&self) -> Money {
+        Money::new(self.currency.round(self.amount), self.currency)
+    }
+
+    /// Ensure that this amount is in the given currency.
+    pub fn check_currency(&self, currency: Currency) -> Result<(), CurrencyError> {
+        if self.currency == currency {
+            Ok(())
+        } else {
+            Err(CurrencyError::Mismatch(self.currency, currency))
+        }
+    }
+}
+
+#[cfg(test)]
+impl Money {
+    // An amount in Australian dollars, for brevity in tests
+    pub(crate) fn aud(amount: Decimal) -> Self {
+        Money::new(amount, Currency::AUD)
+    }
+}
+
+impl std::ops::Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Self::Output {
+        Money::new(-self.amount, self.currency)
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn money_arithmetic() {
+        let a = Money::new(dec!(1.50), Currency::AUD);
+        let b = Money::new(dec!(0.25), Currency::AUD);
+
+        assert_eq!(a.checked_add(b), Ok(Money::new(dec!(1.75), Currency::AUD)));
+        assert_eq!(a.checked_sub(b), Ok(Money::new(dec!(1.25), Currency::AUD)));
+        assert_eq!(-b, Money::new(dec!(-0.25), Currency::AUD));
+    }
+
+    #[test]
+    fn money_mixed_currencies() {
+        let aud = Money::new(dec!(1), Currency::AUD);
+        let usd = Money::new(dec!(1), Currency::USD);
+
+        assert_eq!(
+            aud.checked_add(usd),
+            Err(CurrencyError::Mismatch(Currency::AUD, Currency::USD))
+        );
+    }
+
+    #[test]
+    fn money_display() {
+        assert_eq!(
+            Money::new(dec!(1000), Currency::JPY).to_string(),
+            "1000 JPY"
+        );
+    }
+}
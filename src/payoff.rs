@@ -0,0 +1,339 @@
+use crate::{
+    currency::{Currency, CurrencyError},
+    loan::{Loan, LoanError},
+    money::Money,
+};
+use chrono::{Date, Utc};
+use log::{debug, trace};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+/// The order that surplus money is used to pay down debts. See [`plan_payoff`] for
+/// details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PayoffStrategy {
+    /// Pay down the debt with the smallest balance first
+    Snowball,
+    /// Pay down the debt with the highest interest rate first
+    Avalanche,
+    /// Pay down debts in the given order, by their index in the debts passed to
+    /// [`plan_payoff`]. Any debts that aren't listed are paid down last, in index order.
+    Custom(Vec<usize>),
+}
+
+/// The outcome of paying down debts with a [`PayoffStrategy`]. See [`plan_payoff`] for
+/// details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayoffPlan {
+    strategy: PayoffStrategy,
+    currency: Currency,
+    loans: Vec<Loan>,
+    timeline: Vec<DebtPayoff>,
+}
+
+/// When a single debt is repaid under a [`PayoffPlan`], and how much interest that saves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebtPayoff {
+    debt: usize,
+    payoff_date: Date<Utc>,
+    original_payoff_date: Date<Utc>,
+    interest: Money,
+    interest_saved: Money,
+}
+
+/// Errors encountered whilst planning the payoff of debts.
+#[derive(Error, Debug, PartialEq)]
+pub enum PayoffError {
+    #[error(transparent)]
+    Currency(#[from] CurrencyError),
+    #[error("could not make extra repayment: {0}")]
+    Loan(#[from] LoanError),
+    #[error("there are no debts to pay off")]
+    NoDebts,
+    #[error("there is no debt at index {0}")]
+    InvalidPriority(usize),
+}
+
+impl PayoffPlan {
+    /// The strategy used to allocate the surplus.
+    pub fn get_strategy(&self) -> &PayoffStrategy {
+        &self.strategy
+    }
+
+    /// The debts with the surplus allocated as extra repayments, in the same order as
+    /// the debts passed to [`plan_payoff`].
+    pub fn get_loans(&self) -> &[Loan] {
+        &self.loans
+    }
+
+    /// When each debt is repaid, in payoff date order.
+    pub fn get_timeline(&self) -> &[DebtPayoff] {
+        &self.timeline
+    }
+
+    /// The date that the last debt is repaid.
+    pub fn get_payoff_date(&self) -> Date<Utc> {
+        self.timeline
+            .iter()
+            .map(|p| p.payoff_date)
+            .max()
+            .expect("a plan has at least one debt")
+    }
+
+    /// The total interest charged across every debt.
+    pub fn get_total_interest(&self) -> Money {
+        self.sum(|p| p.interest)
+    }
+
+    /// The total interest saved across every debt, compared to making only the regular
+    /// repayments.
+    pub fn get_interest_saved(&self) -> Money {
+        self.sum(|p| p.interest_saved)
+    }
+
+    fn sum(&self, f: fn(&DebtPayoff) -> Money) -> Money {
+        Money::new(
+            self.timeline.iter().map(|p| f(p).get_amount()).sum(),
+            self.currency,
+        )
+    }
+}
+
+impl DebtPayoff {
+    /// The index of the debt in the debts passed to [`plan_payoff`].
+    pub fn get_debt(&self) -> usize {
+        self.debt
+    }
+
+    /// The date of the debt's final repayment.
+    pub fn get_payoff_date(&self) -> Date<Utc> {
+        self.payoff_date
+    }
+
+    /// The date of the debt's final repayment without any surplus.
+    pub fn get_original_payoff_date(&self) -> Date<Utc> {
+        self.original_payoff_date
+    }
+
+    /// The total interest charged on the debt.
+    pub fn get_interest(&self) -> Money {
+        self.interest
+    }
+
+    /// The interest saved by paying down the debt with surplus.
+    pub fn get_interest_saved(&self) -> Money {
+        self.interest_saved
+    }
+}
+
+/// Allocate surplus money across a collection of debts, and calculate when each debt is
+/// repaid and how much interest that saves.
+///
+/// Each surplus amount is made as an extra repayment on its date, towards the first debt
+/// in the `strategy`'s order that has a balance. If the surplus exceeds that debt's
+/// balance, the remainder rolls over to the next debt, and so on. Debts that haven't
+/// started by a surplus date don't receive any of that surplus.
+///
+/// The surplus usually comes from [`AffordabilityReport::get_monthly_surplus`]. Plan
+/// each strategy in turn to compare them.
+///
+/// Every debt and surplus amount must be in the same currency.
+///
+/// [`AffordabilityReport::get_monthly_surplus`]: crate::AffordabilityReport::get_monthly_surplus
+pub fn plan_payoff(
+    debts: &[Loan],
+    surplus: &[(Date<Utc>, Money)],
+    strategy: PayoffStrategy,
+) -> Result<PayoffPlan, PayoffError> {
+    debug!(
+        "planning payoff of {} debts with {:?}",
+        debts.len(),
+        strategy
+    );
+
+    let currency = debts.first().ok_or(PayoffError::NoDebts)?.get_currency();
+    for debt in debts {
+        debt.get_principal().check_currency(currency)?;
+    }
+    if let PayoffStrategy::Custom(order) = &strategy {
+        if let Some(i) = order.iter().find(|i| **i >= debts.len()) {
+            return Err(PayoffError::InvalidPriority(*i));
+        }
+    }
+
+    let mut surplus = surplus.to_vec();
+    surplus.sort_by_key(|(date, _)| *date);
+
+    let mut loans = debts.to_vec();
+    for (date, amount) in surplus {
+        amount.check_currency(currency)?;
+
+        let mut balances: Vec<(usize, Decimal)> = loans
+            .iter()
+            .enumerate()
+            .filter(|(_, loan)| loan.get_start_date() < date)
+            .map(|(i, loan)| (i, loan.get_balance(date).get_amount()))
+            .filter(|(_, balance)| *balance > Decimal::ZERO)
+            .collect();
+        sort_debts(&mut balances, &loans, &strategy);
+
+        let mut remaining = amount.get_amount();
+        for (i, balance) in balances {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+
+            let extra = remaining.min(balance);
+            trace!("{}: paying {} towards debt {}", date, extra, i);
+            loans[i].add_extra_repayment(date, Money::new(extra, currency))?;
+            remaining -= extra;
+        }
+    }
+
+    let mut timeline: Vec<DebtPayoff> = debts
+        .iter()
+        .zip(&loans)
+        .enumerate()
+        .map(|(i, (original, loan))| {
+            let interest = loan.get_total_interest();
+            DebtPayoff {
+                debt: i,
+                payoff_date: loan.get_payoff_date(),
+                original_payoff_date: original.get_payoff_date(),
+                interest,
+                interest_saved: Money::new(
+                    original.get_total_interest().get_amount() - interest.get_amount(),
+                    currency,
+                ),
+            }
+        })
+        .collect();
+    timeline.sort_by_key(|p| (p.payoff_date, p.debt));
+
+    Ok(PayoffPlan {
+        strategy,
+        currency,
+        loans,
+        timeline,
+    })
+}
+
+// Sort the debts' balances into the order that they should be paid down
+fn sort_debts(balances: &mut [(usize, Decimal)], loans: &[Loan], strategy: &PayoffStrategy) {
+    match strategy {
+        PayoffStrategy::Snowball => balances.sort_by_key(|(i, balance)| (*balance, *i)),
+        PayoffStrategy::Avalanche => {
+            balances.sort_by_key(|(i, balance)| (-loans[*i].get_rate(), *balance, *i))
+        }
+        PayoffStrategy::Custom(order) => balances
+            .sort_by_key(|(i, _)| (order.iter().position(|o| o == i).unwrap_or(order.len()), *i)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::{Clock, FixedClock},
+        frequency::Frequency,
+        loan::{Compounding, LoanTerm},
+        rounding::RoundingPolicy,
+        transaction::{affordability_report, Termination, TransactionModel},
+    };
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn debts() -> Vec<Loan> {
+        let loan = |principal, rate| {
+            Loan::new(
+                Money::aud(principal),
+                rate,
+                Compounding::Repayment,
+                Frequency::MonthlyDate(1, vec![1]),
+                Utc.ymd(2000, 1, 1),
+                LoanTerm::Count(24),
+            )
+            .unwrap()
+        };
+
+        vec![loan(dec!(5000), dec!(20)), loan(dec!(2000), dec!(5))]
+    }
+
+    fn surplus() -> Vec<(Date<Utc>, Money)> {
+        (1..=12)
+            .map(|m| (Utc.ymd(2000, m, 15), Money::aud(dec!(500))))
+            .collect()
+    }
+
+    #[test]
+    fn plan_payoff_snowball() {
+        let plan = plan_payoff(&debts(), &surplus(), PayoffStrategy::Snowball).unwrap();
+
+        // The smaller debt is repaid first
+        let timeline = plan.get_timeline();
+        assert_eq!(timeline[0].get_debt(), 1);
+        assert!(timeline[0].get_payoff_date() < timeline[0].get_original_payoff_date());
+        assert!(timeline[1].get_payoff_date() < timeline[1].get_original_payoff_date());
+        assert!(plan.get_interest_saved().get_amount() > Decimal::ZERO);
+        assert_eq!(
+            plan.get_loans()[1].get_extra_repayments()[0],
+            (Utc.ymd(2000, 1, 15), Money::aud(dec!(500)))
+        );
+    }
+
+    #[test]
+    fn plan_payoff_avalanche() {
+        let avalanche = plan_payoff(&debts(), &surplus(), PayoffStrategy::Avalanche).unwrap();
+        let snowball = plan_payoff(&debts(), &surplus(), PayoffStrategy::Snowball).unwrap();
+
+        // The debt with the higher rate is repaid first, which saves more interest
+        assert_eq!(avalanche.get_timeline()[0].get_debt(), 0);
+        assert!(
+            avalanche.get_interest_saved().get_amount()
+                > snowball.get_interest_saved().get_amount()
+        );
+        assert_eq!(
+            avalanche.get_total_interest().get_amount()
+                + avalanche.get_interest_saved().get_amount(),
+            snowball.get_total_interest().get_amount() + snowball.get_interest_saved().get_amount()
+        );
+    }
+
+    #[test]
+    fn plan_payoff_affordability() {
+        let clock = FixedClock::new(Utc.ymd(2000, 1, 1));
+        let salary = TransactionModel::new_with_clock(
+            Money::aud(dec!(1000)),
+            None,
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Count(12),
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap();
+        let report = affordability_report(Some(&[salary]), None, None).unwrap();
+
+        // The report's unrounded daily amounts are rounded down to a spendable surplus
+        let surplus = report.get_monthly_surplus();
+        assert!(surplus.iter().all(|(_, amount)| amount.is_valid()));
+
+        let plan = plan_payoff(&debts(), &surplus, PayoffStrategy::Avalanche).unwrap();
+        assert!(plan.get_interest_saved().get_amount() > Decimal::ZERO);
+    }
+
+    #[test]
+    fn plan_payoff_custom() {
+        let plan = plan_payoff(&debts(), &surplus(), PayoffStrategy::Custom(vec![1])).unwrap();
+        assert_eq!(plan.get_timeline()[0].get_debt(), 1);
+
+        assert_eq!(
+            plan_payoff(&debts(), &surplus(), PayoffStrategy::Custom(vec![2])),
+            Err(PayoffError::InvalidPriority(2))
+        );
+        assert_eq!(
+            plan_payoff(&[], &surplus(), PayoffStrategy::Snowball),
+            Err(PayoffError::NoDebts)
+        );
+    }
+}
@@ -0,0 +1,440 @@
+use crate::transaction::{
+    affordability_report, AffordabilityResult, TransactionError, TransactionModel,
+};
+use chrono::{Date, Utc};
+use log::debug;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+/// Identifies a [`TransactionModel`] by its index in the expenses or savings passed to
+/// [`resolve_deficit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelRef {
+    Expense(usize),
+    Savings(usize),
+}
+
+/// A reduction applied to a single [`TransactionModel`] in order to resolve a deficit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Amelioration {
+    model: ModelRef,
+    target: Decimal,
+    start_date: Date<Utc>,
+    end_date: Date<Utc>,
+}
+
+/// The outcome of resolving a deficit. See [`resolve_deficit`] for details.
+#[derive(Debug)]
+pub struct Resolution {
+    ameliorations: Vec<Amelioration>,
+    expenses: Vec<TransactionModel>,
+    savings: Vec<TransactionModel>,
+    result: AffordabilityResult,
+}
+
+/// Errors encountered whilst resolving a deficit.
+#[derive(Error, Debug, PartialEq)]
+pub enum ResolveError {
+    #[error("could not ameliorate transaction: {0}")]
+    Transaction(#[from] TransactionError),
+    #[error("there are no transactions that can be ameliorated")]
+    NoCandidates,
+    #[error(
+        "the deficit cannot be resolved by ameliorating transactions (remaining deficit: {0})"
+    )]
+    Unresolvable(Decimal),
+}
+
+// The models that replace each original expense or savings model. Each slot starts with
+// the original model and is replaced wholesale when that model is ameliorated.
+type Slots = Vec<Vec<TransactionModel>>;
+
+impl Amelioration {
+    /// The model that was ameliorated.
+    pub fn get_model(&self) -> ModelRef {
+        self.model
+    }
+
+    /// The reduced payment value.
+    pub fn get_target(&self) -> Decimal {
+        self.target
+    }
+
+    /// The first day of the reduction.
+    pub fn get_start_date(&self) -> Date<Utc> {
+        self.start_date
+    }
+
+    /// The last day of the reduction.
+    pub fn get_end_date(&self) -> Date<Utc> {
+        self.end_date
+    }
+}
+
+impl Resolution {
+    /// The reductions that were applied, in the order they were chosen.
+    pub fn get_ameliorations(&self) -> &[Amelioration] {
+        &self.ameliorations
+    }
+
+    /// The expense models that replace the original expenses.
+    pub fn get_expenses(&self) -> &[TransactionModel] {
+        &self.expenses
+    }
+
+    /// The savings models that replace the original savings.
+    pub fn get_savings(&self) -> &[TransactionModel] {
+        &self.savings
+    }
+
+    /// The affordability of the replacement models, which is never a deficit.
+    pub fn get_result(&self) -> &AffordabilityResult {
+        &self.result
+    }
+
+    /// Consume this `Resolution`, returning the replacement expense and savings models.
+    pub fn into_models(self) -> (Vec<TransactionModel>, Vec<TransactionModel>) {
+        (self.expenses, self.savings)
+    }
+}
+
+/// Resolve a deficit by ameliorating expense and savings [`TransactionModel`]s.
+///
+/// The deficit window spans the first to the last deficit day reported by
+/// [`affordability_report`](crate::affordability_report). Models that can be
+/// ameliorated are reduced over that window, starting with the model that can be reduced
+/// the most. Each model is reduced by as little as possible, so if a single model is
+/// sufficient to resolve the deficit, no other models are touched.
+///
+/// The returned [`Resolution`] contains the replacement expense and savings models,
+/// which include any curtailed originals and restarted models created by
+/// [`TransactionModel::ameliorate`], along with their affordability result. As
+/// amelioration only reduces a model temporarily, a deficit that recurs in perpetuity
+/// cannot be resolved and will return [`ResolveError::Unresolvable`].
+pub fn resolve_deficit(
+    revenues: Option<&[TransactionModel]>,
+    expenses: Option<&[TransactionModel]>,
+    savings: Option<&[TransactionModel]>,
+) -> Result<Resolution, ResolveError> {
+    let expenses = expenses.unwrap_or_default();
+    let savings = savings.unwrap_or_default();
+
+    let mut expense_slots: Slots = expenses.iter().map(|m| vec![m.clone()]).collect();
+    let mut savings_slots: Slots = savings.iter().map(|m| vec![m.clone()]).collect();
+
+    let report = affordability_report(revenues, Some(expenses), Some(savings))?;
+
+    // Find the window that the deficit spans
+    let (start, end) = match report.get_first_deficit_date() {
+        Some(first) => {
+            let last = report
+                .get_days()
+                .iter()
+                .rev()
+                .find(|(_, net)| *net < Decimal::ZERO)
+                .map(|(date, _)| *date)
+                .unwrap_or(first);
+            (first, last)
+        }
+        None => {
+            return Ok(Resolution {
+                ameliorations: Vec::new(),
+                expenses: expenses.to_vec(),
+                savings: savings.to_vec(),
+                result: report.to_result(),
+            })
+        }
+    };
+
+    debug!("resolving deficit from {} to {}", start, end);
+
+    // Choose the models that can be reduced during the deficit window, preferring those
+    // with the most headroom between their value and minimum value.
+    let mut candidates: Vec<(ModelRef, &TransactionModel)> = expenses
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (ModelRef::Expense(i), m))
+        .chain(
+            savings
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (ModelRef::Savings(i), m)),
+        )
+        .filter(|(_, m)| m.can_ameliorate())
+        .filter(|(_, m)| m.get_start_date().is_some_and(|d| d <= end))
+        .filter(|(_, m)| m.get_end_date().is_none_or(|d| d >= start))
+        .collect();
+    candidates.sort_by_key(|(_, m)| {
+        std::cmp::Reverse(m.get_value().get_amount() - m.get_min_value().unwrap_or_default())
+    });
+
+    if candidates.is_empty() {
+        return Err(ResolveError::NoCandidates);
+    }
+
+    let mut ameliorations = Vec::new();
+    let mut remaining = report.get_cumulative_deficit();
+
+    for (model_ref, model) in candidates {
+        // Models can't be ameliorated below their minimum value, so if this doesn't
+        // resolve the deficit, nothing will.
+        let min = model.get_min_value().unwrap();
+        let replacement = ameliorate(model, min, start, end)?;
+        let report = evaluate(
+            revenues,
+            &expense_slots,
+            &savings_slots,
+            model_ref,
+            &replacement,
+        )?;
+
+        let (target, replacement, resolved) = if report.get_first_deficit_date().is_some() {
+            remaining = report.get_cumulative_deficit();
+            (min, replacement, false)
+        } else {
+            // Binary search for the highest value that avoids a deficit, where `low`
+            // always resolves the deficit and `high` never does.
+            let currency = model.get_currency();
+            let step = currency.get_minor_unit();
+            let mut low = min;
+            let mut high = model.get_value().get_amount();
+            let mut best = replacement;
+
+            while high - low > step {
+                let mid = currency.round((low + high) / Decimal::TWO);
+                let candidate = ameliorate(model, mid, start, end)?;
+                let report = evaluate(
+                    revenues,
+                    &expense_slots,
+                    &savings_slots,
+                    model_ref,
+                    &candidate,
+                )?;
+
+                if report.get_first_deficit_date().is_some() {
+                    high = mid;
+                } else {
+                    low = mid;
+                    best = candidate;
+                }
+            }
+
+            (low, best, true)
+        };
+
+        debug!(
+            "ameliorating {:?} to {} from {} to {}",
+            model_ref, target, start, end
+        );
+
+        match model_ref {
+            ModelRef::Expense(i) => expense_slots[i] = replacement,
+            ModelRef::Savings(i) => savings_slots[i] = replacement,
+        }
+
+        ameliorations.push(Amelioration {
+            model: model_ref,
+            target,
+            start_date: start,
+            end_date: end,
+        });
+
+        if resolved {
+            let expenses: Vec<TransactionModel> = expense_slots.into_iter().flatten().collect();
+            let savings: Vec<TransactionModel> = savings_slots.into_iter().flatten().collect();
+            let result =
+                affordability_report(revenues, Some(&expenses), Some(&savings))?.to_result();
+
+            return Ok(Resolution {
+                ameliorations,
+                expenses,
+                savings,
+                result,
+            });
+        }
+    }
+
+    Err(ResolveError::Unresolvable(remaining))
+}
+
+// Ameliorate a copy of `model`, returning the models that replace it
+fn ameliorate(
+    model: &TransactionModel,
+    target: Decimal,
+    start: Date<Utc>,
+    end: Date<Utc>,
+) -> Result<Vec<TransactionModel>, TransactionError> {
+    let mut curtailed = model.clone();
+    let (ameliorated, restarted) = curtailed
+        .ameliorate(target, start, end)
+        .expect("candidate models can always be ameliorated");
+
+    let mut models = Vec::new();
+
+    // The original model may no longer have any contributions if the amelioration
+    // starts on or before its first contribution.
+    if curtailed.get_start_date().is_some() {
+        models.push(curtailed);
+    }
+
+    models.push(ameliorated?);
+
+    if let Some(restarted) = restarted {
+        models.push(restarted?);
+    }
+
+    Ok(models)
+}
+
+// Calculate the affordability of the current models with one slot replaced
+fn evaluate(
+    revenues: Option<&[TransactionModel]>,
+    expense_slots: &[Vec<TransactionModel>],
+    savings_slots: &[Vec<TransactionModel>],
+    model_ref: ModelRef,
+    replacement: &[TransactionModel],
+) -> Result<crate::AffordabilityReport, TransactionError> {
+    let flatten = |slots: &[Vec<TransactionModel>], index: Option<usize>| {
+        slots
+            .iter()
+            .enumerate()
+            .flat_map(|(i, models)| {
+                if Some(i) == index {
+                    replacement.to_vec()
+                } else {
+                    models.clone()
+                }
+            })
+            .collect::<Vec<TransactionModel>>()
+    };
+
+    let (expense_index, savings_index) = match model_ref {
+        ModelRef::Expense(i) => (Some(i), None),
+        ModelRef::Savings(i) => (None, Some(i)),
+    };
+
+    let expenses = flatten(expense_slots, expense_index);
+    let savings = flatten(savings_slots, savings_index);
+
+    affordability_report(revenues, Some(&expenses), Some(&savings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Currency, Frequency, Money};
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn daily(value: Decimal, min_value: Option<Decimal>) -> TransactionModel {
+        let today = Utc.ymd(2000, 4, 1);
+        TransactionModel::new(
+            Money::new(value, Currency::AUD),
+            min_value,
+            Frequency::Daily(1),
+            today,
+            None,
+            Some(today),
+        )
+        .unwrap()
+    }
+
+    fn once(value: Decimal) -> TransactionModel {
+        let today = Utc.ymd(2000, 4, 1);
+        TransactionModel::new(
+            Money::new(value, Currency::AUD),
+            None,
+            Frequency::Once,
+            Utc.ymd(2000, 4, 4),
+            None,
+            Some(today),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_deficit_no_deficit() {
+        let revenues = vec![daily(dec!(2), None)];
+        let expenses = vec![daily(dec!(1), Some(dec!(0.5)))];
+
+        let resolution = resolve_deficit(Some(&revenues), Some(&expenses), None).unwrap();
+
+        assert!(resolution.get_ameliorations().is_empty());
+        assert_eq!(resolution.get_expenses(), expenses.as_slice());
+        assert_eq!(
+            resolution.get_result(),
+            &AffordabilityResult::Surplus(vec![Utc.ymd(2000, 4, 1)])
+        );
+    }
+
+    #[test]
+    fn resolve_deficit_partial_reduction() {
+        let revenues = vec![daily(dec!(2), None)];
+        let expenses = vec![daily(dec!(1), None), daily(dec!(1), Some(dec!(0.5)))];
+        let savings = vec![once(dec!(1))];
+
+        let resolution = resolve_deficit(Some(&revenues), Some(&expenses), Some(&savings)).unwrap();
+
+        assert_eq!(
+            resolution.get_ameliorations(),
+            &[Amelioration {
+                model: ModelRef::Expense(1),
+                target: dec!(0.75),
+                start_date: Utc.ymd(2000, 4, 1),
+                end_date: Utc.ymd(2000, 4, 4),
+            }]
+        );
+        assert_eq!(resolution.get_savings(), savings.as_slice());
+        assert_eq!(resolution.get_result(), &AffordabilityResult::Balanced);
+
+        // The original expense is replaced by the ameliorated and restarted models
+        let (expenses, _) = resolution.into_models();
+        assert_eq!(expenses.len(), 3);
+        assert_eq!(expenses[1].get_value().get_amount(), dec!(0.75));
+        assert_eq!(expenses[2].get_value().get_amount(), dec!(1));
+        assert_eq!(expenses[2].get_start_date(), Some(Utc.ymd(2000, 4, 5)));
+    }
+
+    #[test]
+    fn resolve_deficit_multiple_models() {
+        let revenues = vec![daily(dec!(2), None)];
+        let expenses = vec![daily(dec!(1), Some(dec!(0.8)))];
+        let savings = vec![daily(dec!(0.5), Some(dec!(0.25))), once(dec!(3.6))];
+
+        let resolution = resolve_deficit(Some(&revenues), Some(&expenses), Some(&savings)).unwrap();
+
+        let ameliorations = resolution.get_ameliorations();
+        assert_eq!(ameliorations.len(), 2);
+        assert_eq!(ameliorations[0].get_model(), ModelRef::Savings(0));
+        assert_eq!(ameliorations[0].get_target(), dec!(0.25));
+        assert_eq!(ameliorations[1].get_model(), ModelRef::Expense(0));
+        assert_eq!(ameliorations[1].get_target(), dec!(0.85));
+        assert_eq!(
+            resolution.get_result(),
+            &AffordabilityResult::Surplus(vec![Utc.ymd(2000, 4, 5)])
+        );
+    }
+
+    #[test]
+    fn resolve_deficit_no_candidates() {
+        let revenues = vec![daily(dec!(1), None)];
+        let expenses = vec![daily(dec!(2), None)];
+
+        assert_eq!(
+            resolve_deficit(Some(&revenues), Some(&expenses), None).err(),
+            Some(ResolveError::NoCandidates)
+        );
+    }
+
+    #[test]
+    fn resolve_deficit_unresolvable() {
+        let revenues = vec![daily(dec!(2), None)];
+        let expenses = vec![daily(dec!(1), Some(dec!(0.9)))];
+        let savings = vec![once(dec!(5))];
+
+        assert_eq!(
+            resolve_deficit(Some(&revenues), Some(&expenses), Some(&savings)).err(),
+            Some(ResolveError::Unresolvable(dec!(0.6)))
+        );
+    }
+}
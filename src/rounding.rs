@@ -0,0 +1,69 @@
+use crate::currency::Currency;
+use rust_decimal::{Decimal, RoundingStrategy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How daily contribution amounts are rounded.
+///
+/// Contributions are calculated by dividing payments over a number of days, which rarely
+/// produces an amount that can actually be transferred (e.g. 0.0333...). Every policy
+/// other than `Exact` rounds contributions to the currency's minor unit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RoundingPolicy {
+    /// Contributions are calculated with full `Decimal` precision, with any rounding
+    /// error absorbed by the final day of each period
+    #[default]
+    Exact,
+    /// The running total of each period's contributions is rounded down, so each day
+    /// carries its remainder forward to the next
+    CarryForward,
+    /// Every contribution is rounded up, accruing a small surplus over each period
+    RoundUp,
+    /// The running total of each period's contributions uses banker's rounding (round
+    /// half to even), so each day carries its remainder forward to the next
+    Bankers,
+}
+
+impl RoundingPolicy {
+    /// Round a contribution amount according to this policy.
+    pub fn round(&self, amount: Decimal, currency: Currency) -> Decimal {
+        let dp = currency.get_minor_units();
+        match *self {
+            RoundingPolicy::Exact => amount,
+            RoundingPolicy::CarryForward => {
+                amount.round_dp_with_strategy(dp, RoundingStrategy::ToZero)
+            }
+            RoundingPolicy::RoundUp => {
+                amount.round_dp_with_strategy(dp, RoundingStrategy::AwayFromZero)
+            }
+            RoundingPolicy::Bankers => {
+                amount.round_dp_with_strategy(dp, RoundingStrategy::MidpointNearestEven)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn round_policies() {
+        let aud = Currency::AUD;
+
+        assert_eq!(RoundingPolicy::Exact.round(dec!(0.125), aud), dec!(0.125));
+        assert_eq!(
+            RoundingPolicy::CarryForward.round(dec!(0.129), aud),
+            dec!(0.12)
+        );
+        assert_eq!(RoundingPolicy::RoundUp.round(dec!(0.121), aud), dec!(0.13));
+        assert_eq!(RoundingPolicy::Bankers.round(dec!(0.125), aud), dec!(0.12));
+        assert_eq!(RoundingPolicy::Bankers.round(dec!(0.135), aud), dec!(0.14));
+        assert_eq!(
+            RoundingPolicy::RoundUp.round(dec!(10.1), Currency::JPY),
+            dec!(11)
+        );
+    }
+}
@@ -0,0 +1,670 @@
+use std::collections::HashMap;
+
+use crate::frequency::{Frequency, FrequencyMonthDay};
+use thiserror::Error;
+
+// iCalendar weekday codes, indexed by `day - 1` where `day` = 1 (Monday), ..., 7 (Sunday)
+const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// Errors encountered whilst converting between a [`Frequency`] and an iCalendar
+/// (RFC 5545) recurrence rule.
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum RRuleError {
+    #[error("the rule does not specify a FREQ")]
+    MissingFrequency,
+    #[error("the rule part {0} is required for this frequency")]
+    MissingPart(&'static str),
+    #[error("the rule part {0} appears more than once")]
+    DuplicatePart(String),
+    #[error("'{1}' is not a valid value for {0}")]
+    InvalidValue(String, String),
+    #[error("the rule part {0} cannot be expressed by a Frequency")]
+    UnsupportedPart(String),
+    #[error("the rule cannot be expressed: {0}")]
+    Unrepresentable(String),
+}
+
+impl Frequency {
+    /// Parse a `Frequency` from an iCalendar (RFC 5545) `RRULE` value.
+    ///
+    /// The rule may optionally be prefixed with `RRULE:`. Terminating parts (`UNTIL`
+    /// and `COUNT`) belong to a `TransactionModel` rather than its `Frequency`, so they
+    /// are rejected, with the exception of `COUNT=1`, which is parsed as
+    /// `Frequency::Once`.
+    ///
+    /// Rules that rely on `DTSTART` to fill in missing parts (e.g. `FREQ=WEEKLY`
+    /// without `BYDAY`) cannot be expressed, as a `Frequency` has no start date.
+    pub fn from_rrule(rule: &str) -> Result<Frequency, RRuleError> {
+        let rule = rule.trim();
+        let rule = match rule.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &rule[6..],
+            _ => rule,
+        };
+
+        let mut parts = HashMap::new();
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| RRuleError::InvalidValue("RRULE".into(), part.into()))?;
+            let key = key.trim().to_ascii_uppercase();
+            let value = value.trim().to_ascii_uppercase();
+
+            if parts.insert(key.clone(), value).is_some() {
+                return Err(RRuleError::DuplicatePart(key));
+            }
+        }
+
+        for key in parts.keys() {
+            match key.as_str() {
+                "FREQ" | "INTERVAL" | "COUNT" | "WKST" | "BYDAY" | "BYMONTHDAY" | "BYMONTH"
+                | "BYSETPOS" => (),
+                _ => return Err(RRuleError::UnsupportedPart(key.clone())),
+            }
+        }
+
+        // A `Frequency` has no notion of an end, other than a single payment
+        if let Some(count) = parts.get("COUNT") {
+            if count == "1" {
+                return Ok(Frequency::Once);
+            }
+            return Err(RRuleError::UnsupportedPart("COUNT".into()));
+        }
+
+        // Weeks always start on a Monday, so we can't honour any other week start
+        if let Some(wkst) = parts.get("WKST") {
+            if wkst != "MO" {
+                return Err(RRuleError::InvalidValue("WKST".into(), wkst.clone()));
+            }
+        }
+
+        let interval = match parts.get("INTERVAL") {
+            Some(i) => match i.parse::<u32>() {
+                Ok(i) if i > 0 => i,
+                _ => return Err(RRuleError::InvalidValue("INTERVAL".into(), i.clone())),
+            },
+            None => 1,
+        };
+
+        let freq = parts.get("FREQ").ok_or(RRuleError::MissingFrequency)?;
+        let by_day = parts.get("BYDAY");
+        let by_month_day = parts.get("BYMONTHDAY");
+        let by_month = parts.get("BYMONTH");
+        let by_set_pos = parts.get("BYSETPOS");
+
+        match freq.as_str() {
+            "DAILY" => {
+                reject_parts(&parts, &["BYDAY", "BYMONTHDAY", "BYMONTH", "BYSETPOS"])?;
+                Ok(Frequency::Daily(interval))
+            }
+            "WEEKLY" => {
+                reject_parts(&parts, &["BYMONTHDAY", "BYMONTH", "BYSETPOS"])?;
+                let by_day = by_day.ok_or(RRuleError::MissingPart("BYDAY"))?;
+
+                let mut days = Vec::new();
+                for d in by_day.split(',') {
+                    match parse_weekday(d)? {
+                        (None, day) => days.push(day),
+                        (Some(_), _) => {
+                            return Err(RRuleError::InvalidValue("BYDAY".into(), d.into()))
+                        }
+                    }
+                }
+                days.sort_unstable();
+                days.dedup();
+
+                Ok(Frequency::Weekly(interval, days))
+            }
+            "MONTHLY" => {
+                reject_parts(&parts, &["BYMONTH"])?;
+
+                match (by_day, by_month_day) {
+                    (Some(by_day), None) => {
+                        let (nth, day) = parse_month_day(by_day, by_set_pos)?;
+                        Ok(Frequency::MonthlyDay(interval, nth, day))
+                    }
+                    (None, Some(by_month_day)) => {
+                        reject_parts(&parts, &["BYSETPOS"])?;
+
+                        // The last day of the month is expressed as a negative offset
+                        if by_month_day == "-1" {
+                            return Ok(Frequency::MonthlyDay(interval, 0, FrequencyMonthDay::Day));
+                        }
+
+                        let mut dates = by_month_day
+                            .split(',')
+                            .map(|d| parse_number("BYMONTHDAY", d, 1, 31))
+                            .collect::<Result<Vec<u32>, RRuleError>>()?;
+                        dates.sort_unstable();
+                        dates.dedup();
+
+                        Ok(Frequency::MonthlyDate(interval, dates))
+                    }
+                    (Some(_), Some(_)) => Err(RRuleError::Unrepresentable(
+                        "BYDAY and BYMONTHDAY cannot be combined".into(),
+                    )),
+                    (None, None) => Err(RRuleError::MissingPart("BYDAY or BYMONTHDAY")),
+                }
+            }
+            "YEARLY" => {
+                let by_month = by_month.ok_or(RRuleError::MissingPart("BYMONTH"))?;
+                let mut months = by_month
+                    .split(',')
+                    .map(|m| parse_number("BYMONTH", m, 1, 12))
+                    .collect::<Result<Vec<u32>, RRuleError>>()?;
+                months.sort_unstable();
+                months.dedup();
+
+                match (by_day, by_month_day) {
+                    (Some(by_day), None) => {
+                        let (nth, day) = parse_month_day(by_day, by_set_pos)?;
+
+                        // BYSETPOS applies to the set of dates for the whole year, so it
+                        // only selects the nth day of a month if there is a single month.
+                        if by_set_pos.is_some() && months.len() > 1 {
+                            return Err(RRuleError::Unrepresentable(
+                                "BYSETPOS can only be used with a single BYMONTH".into(),
+                            ));
+                        }
+
+                        Ok(Frequency::Yearly(interval, months, Some(nth), Some(day)))
+                    }
+                    (None, Some(by_month_day)) => {
+                        reject_parts(&parts, &["BYSETPOS"])?;
+                        let nth = if by_month_day == "-1" {
+                            0
+                        } else {
+                            parse_number("BYMONTHDAY", by_month_day, 1, 31)?
+                        };
+
+                        Ok(Frequency::Yearly(
+                            interval,
+                            months,
+                            Some(nth),
+                            Some(FrequencyMonthDay::Day),
+                        ))
+                    }
+                    (Some(_), Some(_)) => Err(RRuleError::Unrepresentable(
+                        "BYDAY and BYMONTHDAY cannot be combined".into(),
+                    )),
+                    (None, None) => {
+                        reject_parts(&parts, &["BYSETPOS"])?;
+                        Ok(Frequency::Yearly(interval, months, None, None))
+                    }
+                }
+            }
+            _ => Err(RRuleError::InvalidValue("FREQ".into(), freq.clone())),
+        }
+    }
+
+    /// Export this `Frequency` as an iCalendar (RFC 5545) `RRULE` value.
+    ///
+    /// The returned rule does not include the `RRULE:` prefix. Note that
+    /// `Frequency::Once` is exported as a daily rule with a `COUNT` of 1.
+    pub fn to_rrule(&self) -> Result<String, RRuleError> {
+        let rule = match *self {
+            Frequency::Once => "FREQ=DAILY;COUNT=1".to_owned(),
+            Frequency::Daily(n) => with_interval("DAILY", n),
+            Frequency::Weekly(n, ref days) => {
+                let days = days
+                    .iter()
+                    .map(|d| weekday_code(*d))
+                    .collect::<Result<Vec<&str>, RRuleError>>()?;
+                format!("{};BYDAY={}", with_interval("WEEKLY", n), days.join(","))
+            }
+            Frequency::MonthlyDate(n, ref dates) => {
+                let dates = dates
+                    .iter()
+                    .map(|d| match *d {
+                        1..=31 => Ok(d.to_string()),
+                        _ => Err(RRuleError::InvalidValue("BYMONTHDAY".into(), d.to_string())),
+                    })
+                    .collect::<Result<Vec<String>, RRuleError>>()?;
+                format!(
+                    "{};BYMONTHDAY={}",
+                    with_interval("MONTHLY", n),
+                    dates.join(",")
+                )
+            }
+            Frequency::MonthlyDay(n, nth, ref day) => {
+                format!(
+                    "{};{}",
+                    with_interval("MONTHLY", n),
+                    month_day_parts(nth, day)?
+                )
+            }
+            Frequency::Yearly(n, ref months, nth, ref day) => {
+                let month_list = months
+                    .iter()
+                    .map(|m| match *m {
+                        1..=12 => Ok(m.to_string()),
+                        _ => Err(RRuleError::InvalidValue("BYMONTH".into(), m.to_string())),
+                    })
+                    .collect::<Result<Vec<String>, RRuleError>>()?;
+                let mut rule = format!(
+                    "{};BYMONTH={}",
+                    with_interval("YEARLY", n),
+                    month_list.join(",")
+                );
+
+                match (nth, day) {
+                    (Some(nth), Some(day)) => {
+                        // See `from_rrule` - BYSETPOS spans every month in the year
+                        if months.len() > 1
+                            && matches!(
+                                day,
+                                FrequencyMonthDay::Weekday | FrequencyMonthDay::Weekend
+                            )
+                        {
+                            return Err(RRuleError::Unrepresentable(format!(
+                                "the nth {} of multiple months requires BYSETPOS per month",
+                                day
+                            )));
+                        }
+                        rule.push(';');
+                        rule.push_str(&month_day_parts(nth, day)?);
+                    }
+                    (None, None) => (),
+                    _ => {
+                        return Err(RRuleError::Unrepresentable(
+                            "yearly frequencies must set both nth and day, or neither".into(),
+                        ))
+                    }
+                }
+
+                rule
+            }
+            Frequency::Adjusted(..) => {
+                return Err(RRuleError::Unrepresentable(
+                    "business day adjustments have no RRULE equivalent".into(),
+                ))
+            }
+        };
+
+        Ok(rule)
+    }
+}
+
+// Error if any of the given parts are present in the rule
+fn reject_parts(parts: &HashMap<String, String>, keys: &[&str]) -> Result<(), RRuleError> {
+    match keys.iter().find(|k| parts.contains_key(**k)) {
+        Some(k) => Err(RRuleError::UnsupportedPart((*k).into())),
+        None => Ok(()),
+    }
+}
+
+// Parse an unsigned integer that must fall within `min..=max`
+fn parse_number(part: &str, value: &str, min: u32, max: u32) -> Result<u32, RRuleError> {
+    match value.trim_start_matches('+').parse::<u32>() {
+        Ok(n) if n >= min && n <= max => Ok(n),
+        _ => Err(RRuleError::InvalidValue(part.into(), value.into())),
+    }
+}
+
+// Parse an ordinal, where 1..=5 map to first..fifth and -1 maps to 'last' (0)
+fn parse_ordinal(part: &str, value: &str) -> Result<u32, RRuleError> {
+    if value == "-1" {
+        Ok(0)
+    } else {
+        parse_number(part, value, 1, 5)
+    }
+}
+
+// Parse a BYDAY weekday, e.g. "MO", "2TU" or "-1FR", into an optional ordinal and a day
+// number (1 = Monday, ..., 7 = Sunday).
+fn parse_weekday(value: &str) -> Result<(Option<u32>, u32), RRuleError> {
+    if !value.is_ascii() {
+        return Err(RRuleError::InvalidValue("BYDAY".into(), value.into()));
+    }
+
+    let split = value.len().saturating_sub(2);
+    let (ordinal, code) = value.split_at(split);
+    let day = WEEKDAY_CODES
+        .iter()
+        .position(|c| *c == code)
+        .ok_or_else(|| RRuleError::InvalidValue("BYDAY".into(), value.into()))?
+        as u32
+        + 1;
+
+    if ordinal.is_empty() {
+        Ok((None, day))
+    } else {
+        Ok((Some(parse_ordinal("BYDAY", ordinal)?), day))
+    }
+}
+
+// Parse the BYDAY (and optional BYSETPOS) parts for a monthly or yearly rule into an nth
+// value and a `FrequencyMonthDay`.
+fn parse_month_day(
+    by_day: &str,
+    by_set_pos: Option<&String>,
+) -> Result<(u32, FrequencyMonthDay), RRuleError> {
+    let days = by_day
+        .split(',')
+        .map(parse_weekday)
+        .collect::<Result<Vec<(Option<u32>, u32)>, RRuleError>>()?;
+
+    if let Some(set_pos) = by_set_pos {
+        let nth = parse_ordinal("BYSETPOS", set_pos)?;
+
+        if days.iter().any(|(n, _)| n.is_some()) {
+            return Err(RRuleError::InvalidValue("BYDAY".into(), by_day.into()));
+        }
+
+        let mut day_nums: Vec<u32> = days.iter().map(|(_, d)| *d).collect();
+        day_nums.sort_unstable();
+        day_nums.dedup();
+
+        return match day_nums.as_slice() {
+            [1, 2, 3, 4, 5] => Ok((nth, FrequencyMonthDay::Weekday)),
+            [6, 7] => Ok((nth, FrequencyMonthDay::Weekend)),
+            [d] => Ok((nth, month_day_from_number(*d))),
+            _ => Err(RRuleError::Unrepresentable(format!(
+                "BYSETPOS can only select from a single weekday, all week days or the weekend, not {}",
+                by_day
+            ))),
+        };
+    }
+
+    match days.as_slice() {
+        [(Some(nth), day)] => Ok((*nth, month_day_from_number(*day))),
+        [(None, _)] => Err(RRuleError::Unrepresentable(format!(
+            "every {} of the month is not a single payment date",
+            by_day
+        ))),
+        _ => Err(RRuleError::Unrepresentable(format!(
+            "multiple days ({}) require BYSETPOS",
+            by_day
+        ))),
+    }
+}
+
+// Create the BYDAY/BYMONTHDAY/BYSETPOS parts for an nth day of the month
+fn month_day_parts(nth: u32, day: &FrequencyMonthDay) -> Result<String, RRuleError> {
+    let ordinal = match nth {
+        0 => "-1".to_owned(),
+        1..=5 => nth.to_string(),
+        _ if *day == FrequencyMonthDay::Day && nth <= 31 => nth.to_string(),
+        _ => return Err(RRuleError::InvalidValue("nth".into(), nth.to_string())),
+    };
+
+    Ok(match *day {
+        FrequencyMonthDay::Day => format!("BYMONTHDAY={}", ordinal),
+        FrequencyMonthDay::Weekday => format!("BYDAY=MO,TU,WE,TH,FR;BYSETPOS={}", ordinal),
+        FrequencyMonthDay::Weekend => format!("BYDAY=SA,SU;BYSETPOS={}", ordinal),
+        _ => format!(
+            "BYDAY={}{}",
+            ordinal,
+            weekday_code(month_day_to_number(day))?
+        ),
+    })
+}
+
+// Add the interval part to a rule, omitting it when it's the default (1)
+fn with_interval(freq: &str, interval: u32) -> String {
+    if interval == 1 {
+        format!("FREQ={}", freq)
+    } else {
+        format!("FREQ={};INTERVAL={}", freq, interval)
+    }
+}
+
+fn weekday_code(day: u32) -> Result<&'static str, RRuleError> {
+    match day {
+        1..=7 => Ok(WEEKDAY_CODES[day as usize - 1]),
+        _ => Err(RRuleError::InvalidValue("BYDAY".into(), day.to_string())),
+    }
+}
+
+fn month_day_from_number(day: u32) -> FrequencyMonthDay {
+    match day {
+        1 => FrequencyMonthDay::Monday,
+        2 => FrequencyMonthDay::Tuesday,
+        3 => FrequencyMonthDay::Wednesday,
+        4 => FrequencyMonthDay::Thursday,
+        5 => FrequencyMonthDay::Friday,
+        6 => FrequencyMonthDay::Saturday,
+        7 => FrequencyMonthDay::Sunday,
+        _ => unreachable!(), // only 7 days in a week!
+    }
+}
+
+fn month_day_to_number(day: &FrequencyMonthDay) -> u32 {
+    match *day {
+        FrequencyMonthDay::Monday => 1,
+        FrequencyMonthDay::Tuesday => 2,
+        FrequencyMonthDay::Wednesday => 3,
+        FrequencyMonthDay::Thursday => 4,
+        FrequencyMonthDay::Friday => 5,
+        FrequencyMonthDay::Saturday => 6,
+        FrequencyMonthDay::Sunday => 7,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_rrule_once() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=DAILY;COUNT=1"),
+            Ok(Frequency::Once)
+        );
+    }
+
+    #[test]
+    fn from_rrule_daily_prefix() {
+        assert_eq!(
+            Frequency::from_rrule("RRULE:FREQ=DAILY;INTERVAL=3"),
+            Ok(Frequency::Daily(3))
+        );
+    }
+
+    #[test]
+    fn from_rrule_weekly() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=WEEKLY;INTERVAL=2;BYDAY=FR,MO;WKST=MO"),
+            Ok(Frequency::Weekly(2, vec![1, 5]))
+        );
+    }
+
+    #[test]
+    fn from_rrule_monthly_date() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=MONTHLY;BYMONTHDAY=15,1"),
+            Ok(Frequency::MonthlyDate(1, vec![1, 15]))
+        );
+    }
+
+    #[test]
+    fn from_rrule_monthly_last_day() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=-1"),
+            Ok(Frequency::MonthlyDay(3, 0, FrequencyMonthDay::Day))
+        );
+    }
+
+    #[test]
+    fn from_rrule_monthly_last_friday() {
+        assert_eq!(
+            Frequency::from_rrule("freq=monthly;byday=-1fr"),
+            Ok(Frequency::MonthlyDay(1, 0, FrequencyMonthDay::Friday))
+        );
+    }
+
+    #[test]
+    fn from_rrule_monthly_weekday() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=2"),
+            Ok(Frequency::MonthlyDay(1, 2, FrequencyMonthDay::Weekday))
+        );
+    }
+
+    #[test]
+    fn from_rrule_monthly_weekend() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=MONTHLY;BYDAY=SU,SA;BYSETPOS=-1"),
+            Ok(Frequency::MonthlyDay(1, 0, FrequencyMonthDay::Weekend))
+        );
+    }
+
+    #[test]
+    fn from_rrule_yearly() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=YEARLY;INTERVAL=2;BYMONTH=8,2"),
+            Ok(Frequency::Yearly(2, vec![2, 8], None, None))
+        );
+    }
+
+    #[test]
+    fn from_rrule_yearly_nth_day() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=YEARLY;BYMONTH=6;BYDAY=2MO"),
+            Ok(Frequency::Yearly(
+                1,
+                vec![6],
+                Some(2),
+                Some(FrequencyMonthDay::Monday)
+            ))
+        );
+    }
+
+    #[test]
+    fn from_rrule_yearly_month_day() {
+        assert_eq!(
+            Frequency::from_rrule("FREQ=YEARLY;BYMONTH=7;BYMONTHDAY=15"),
+            Ok(Frequency::Yearly(
+                1,
+                vec![7],
+                Some(15),
+                Some(FrequencyMonthDay::Day)
+            ))
+        );
+    }
+
+    #[test]
+    fn from_rrule_errors() {
+        assert_eq!(
+            Frequency::from_rrule("INTERVAL=2"),
+            Err(RRuleError::MissingFrequency)
+        );
+        assert_eq!(
+            Frequency::from_rrule("FREQ=HOURLY"),
+            Err(RRuleError::InvalidValue("FREQ".into(), "HOURLY".into()))
+        );
+        assert_eq!(
+            Frequency::from_rrule("FREQ=WEEKLY"),
+            Err(RRuleError::MissingPart("BYDAY"))
+        );
+        assert_eq!(
+            Frequency::from_rrule("FREQ=DAILY;UNTIL=20000401"),
+            Err(RRuleError::UnsupportedPart("UNTIL".into()))
+        );
+        assert_eq!(
+            Frequency::from_rrule("FREQ=DAILY;COUNT=5"),
+            Err(RRuleError::UnsupportedPart("COUNT".into()))
+        );
+        assert_eq!(
+            Frequency::from_rrule("FREQ=DAILY;FREQ=WEEKLY"),
+            Err(RRuleError::DuplicatePart("FREQ".into()))
+        );
+        assert_eq!(
+            Frequency::from_rrule("FREQ=MONTHLY;BYDAY=-2FR"),
+            Err(RRuleError::InvalidValue("BYDAY".into(), "-2".into()))
+        );
+        assert!(matches!(
+            Frequency::from_rrule("FREQ=YEARLY;BYMONTH=1,2;BYDAY=SA,SU;BYSETPOS=1"),
+            Err(RRuleError::Unrepresentable(_))
+        ));
+    }
+
+    #[test]
+    fn to_rrule_variants() {
+        assert_eq!(Frequency::Once.to_rrule().unwrap(), "FREQ=DAILY;COUNT=1");
+        assert_eq!(Frequency::Daily(1).to_rrule().unwrap(), "FREQ=DAILY");
+        assert_eq!(
+            Frequency::Weekly(2, vec![1, 5]).to_rrule().unwrap(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR"
+        );
+        assert_eq!(
+            Frequency::MonthlyDate(1, vec![1, 15]).to_rrule().unwrap(),
+            "FREQ=MONTHLY;BYMONTHDAY=1,15"
+        );
+        assert_eq!(
+            Frequency::MonthlyDay(1, 0, FrequencyMonthDay::Friday)
+                .to_rrule()
+                .unwrap(),
+            "FREQ=MONTHLY;BYDAY=-1FR"
+        );
+        assert_eq!(
+            Frequency::MonthlyDay(1, 3, FrequencyMonthDay::Weekday)
+                .to_rrule()
+                .unwrap(),
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=3"
+        );
+        assert_eq!(
+            Frequency::Yearly(1, vec![1, 2], Some(0), Some(FrequencyMonthDay::Day))
+                .to_rrule()
+                .unwrap(),
+            "FREQ=YEARLY;BYMONTH=1,2;BYMONTHDAY=-1"
+        );
+    }
+
+    #[test]
+    fn to_rrule_unrepresentable() {
+        let freq = Frequency::Yearly(1, vec![1, 2], Some(1), Some(FrequencyMonthDay::Weekend));
+        assert!(matches!(
+            freq.to_rrule(),
+            Err(RRuleError::Unrepresentable(_))
+        ));
+        assert_eq!(
+            Frequency::Weekly(1, vec![8]).to_rrule(),
+            Err(RRuleError::InvalidValue("BYDAY".into(), "8".into()))
+        );
+    }
+
+    #[test]
+    fn rrule_round_trip() {
+        let frequencies = vec![
+            Frequency::Once,
+            Frequency::Daily(4),
+            Frequency::Weekly(3, vec![2, 6, 7]),
+            Frequency::MonthlyDate(6, vec![27, 31]),
+            Frequency::MonthlyDay(2, 4, FrequencyMonthDay::Thursday),
+            Frequency::MonthlyDay(1, 0, FrequencyMonthDay::Weekday),
+            Frequency::MonthlyDay(1, 2, FrequencyMonthDay::Weekend),
+            Frequency::MonthlyDay(1, 0, FrequencyMonthDay::Day),
+            Frequency::Yearly(1, vec![3, 9], None, None),
+            Frequency::Yearly(2, vec![5], Some(0), Some(FrequencyMonthDay::Weekend)),
+            Frequency::Yearly(1, vec![1, 12], Some(1), Some(FrequencyMonthDay::Sunday)),
+        ];
+
+        for freq in frequencies {
+            let rule = freq.to_rrule().unwrap();
+            assert_eq!(Frequency::from_rrule(&rule), Ok(freq), "rule: {}", rule);
+        }
+    }
+
+    #[test]
+    fn from_rrule_to_string() {
+        let display = |rule: &str| Frequency::from_rrule(rule).unwrap().to_string();
+
+        assert_eq!(
+            display("FREQ=YEARLY;BYMONTH=7;BYMONTHDAY=15"),
+            "yearly payments in July, on the 15th day"
+        );
+        assert_eq!(
+            display("FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=22"),
+            "payments every 2 months on 22nd"
+        );
+        assert_eq!(
+            display("FREQ=MONTHLY;BYMONTHDAY=-1"),
+            "monthly payments on the last day"
+        );
+        assert_eq!(
+            Frequency::MonthlyDay(1, 11, FrequencyMonthDay::Day).to_string(),
+            "monthly payments on the 11th day"
+        );
+    }
+}
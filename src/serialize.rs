@@ -0,0 +1,86 @@
+// Serde helpers for `chrono` types that don't implement `Serialize`/`Deserialize`.
+//
+// `Date<Utc>` is serialized as an ISO 8601 date (e.g. "2000-04-01") and `Duration` as a
+// whole number of days, which is the only resolution this crate works in.
+
+pub(crate) mod date {
+    use chrono::{Date, NaiveDate, Utc};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    const FORMAT: &str = "%Y-%m-%d";
+
+    pub fn serialize<S: Serializer>(date: &Date<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&date.format(FORMAT))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Date<Utc>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        NaiveDate::parse_from_str(&s, FORMAT)
+            .map(|d| Date::from_utc(d, Utc))
+            .map_err(D::Error::custom)
+    }
+}
+
+pub(crate) mod option_date {
+    use chrono::{Date, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    // Wrapper that allows us to reuse `date` for the inner value
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "super::date")] Date<Utc>);
+
+    pub fn serialize<S: Serializer>(
+        date: &Option<Date<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        date.map(Wrapper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Date<Utc>>, D::Error> {
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|w| w.0))
+    }
+}
+
+pub(crate) mod duration {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(duration.num_days())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::days(i64::deserialize(deserializer)?))
+    }
+}
+
+pub(crate) mod date_map {
+    use std::collections::BTreeMap;
+
+    use chrono::{Date, NaiveDate, Utc};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    const FORMAT: &str = "%Y-%m-%d";
+
+    pub fn serialize<S: Serializer, V: Serialize>(
+        map: &BTreeMap<Date<Utc>, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().map(|(d, v)| (d.format(FORMAT).to_string(), v)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<Date<Utc>, V>, D::Error> {
+        BTreeMap::<String, V>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(k, v)| {
+                NaiveDate::parse_from_str(&k, FORMAT)
+                    .map(|d| (Date::from_utc(d, Utc), v))
+                    .map_err(D::Error::custom)
+            })
+            .collect()
+    }
+}
@@ -1,13 +1,22 @@
-use std::collections::HashMap;
+use std::{collections::BTreeMap, convert::Infallible, iter};
 
 use crate::{
-    contribution::{calculate, Contribution, ContributionError},
+    amounts::AmountSchedule,
+    clock::{Clock, FixedClock, SystemClock},
+    contribution::{calculate, Contribution, ContributionError, PaymentAmounts},
+    currency::{Currency, CurrencyError},
+    escalation::Escalation,
+    exchange::{ExchangeError, ExchangeRates},
     frequency::Frequency,
-    CURRENCY_PRECISION,
+    ledger::Payment,
+    money::Money,
+    rounding::RoundingPolicy,
 };
-use chrono::{Date, Utc};
+use chrono::{Date, Datelike, Duration, Utc};
 use log::{debug, trace};
 use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// The representation of a future transaction.
@@ -15,12 +24,38 @@ use thiserror::Error;
 /// `TransactionModel`s are the building blocks of a budget, and are used to track
 /// revenues, expenses and savings over time. These models are also used to calculate the
 /// affordability of a user's finances in perpetuity.
-#[derive(Debug)]
+///
+/// With the `serde` feature enabled, a `TransactionModel` can be serialized along with
+/// its calculated contributions. Deserializing a model restores those contributions
+/// as-is, rather than recalculating them against a new calculation date.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TransactionModel {
-    value: Decimal,
+    value: Money,
     min_value: Option<Decimal>,
     contributions: Vec<Contribution>,
     frequency: Frequency,
+    // The date that payments are scheduled from. Note that this can differ from the
+    // start of the first contribution, which may begin early to make use of lead time.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::date"))]
+    start_date: Date<Utc>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    rounding: RoundingPolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    amounts: Option<AmountSchedule>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    escalation: Option<Escalation>,
+}
+
+/// The condition that ends a recurring [`TransactionModel`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Termination {
+    /// The transaction repeats forever
+    Never,
+    /// The transaction repeats until the given date (inclusive)
+    EndDate(Date<Utc>),
+    /// The transaction ends after `n` payments
+    Count(u32),
 }
 
 /// Errors encountered whilst working with [`TransactionModel`]s.
@@ -28,10 +63,25 @@ pub struct TransactionModel {
 pub enum TransactionError {
     #[error("could not calculate contributions: {0}")]
     Contribution(#[from] ContributionError),
-    #[error("currency values cannot have more than 2 decimal places: {0}")]
-    CurrencyPrecision(Decimal),
+    #[error(
+        "{currency} values cannot have more than {places} decimal places: {amount}",
+        currency = .0.get_currency(),
+        places = .0.get_currency().get_minor_units(),
+        amount = .0.get_amount()
+    )]
+    CurrencyPrecision(Money),
+    #[error(transparent)]
+    Currency(#[from] CurrencyError),
+    #[error(transparent)]
+    Exchange(#[from] ExchangeError),
     #[error("no contributions could be calculated for this model")]
     EmptyContributions,
+    #[error("the frequency does not have {0} payments")]
+    PaymentCount(u32),
+    #[error("there is no expected payment for the actual payment on {0}")]
+    UnexpectedPayment(Date<Utc>),
+    #[error("a model with annual escalation must end")]
+    UnboundedEscalation,
 }
 
 /// The result of an affordability calculation. See [`is_affordable`] for details.
@@ -46,15 +96,49 @@ pub enum AffordabilityResult {
     Surplus(Vec<Date<Utc>>),
 }
 
+/// A single day's contribution towards a [`TransactionModel`]'s payments. See
+/// [`TransactionModel::schedule`] for details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduledContribution {
+    date: Date<Utc>,
+    amount: Money,
+    segment: usize,
+    payments: Vec<Date<Utc>>,
+}
+
+/// A quantified breakdown of an affordability calculation. See [`affordability_report`]
+/// for details.
+///
+/// Deficit amounts are reported as positive values, representing how far short the
+/// budget is, whereas each day's net amount is negative on deficit days.
+#[derive(PartialEq, Eq, Debug)]
+pub struct AffordabilityReport {
+    currency: Option<Currency>,
+    days: Vec<(Date<Utc>, Decimal)>,
+    first_deficit: Option<Date<Utc>>,
+    largest_deficit: Option<(Date<Utc>, Decimal)>,
+    cumulative_deficit: Decimal,
+    cumulative_surplus: Decimal,
+}
+
 // Used internally to determine whether a contribution should be added to or subtracted
 // from a daily total. For example, revenues are `Positive` and should be added, whilst
 // expenses are `Negative` and should be subtracted.
 #[derive(Debug)]
-enum ContributionSign<'a> {
+pub(crate) enum ContributionSign<'a> {
     Positive(&'a Contribution),
     Negative(&'a Contribution),
 }
 
+impl From<Option<Date<Utc>>> for Termination {
+    fn from(end_date: Option<Date<Utc>>) -> Self {
+        match end_date {
+            Some(date) => Termination::EndDate(date),
+            None => Termination::Never,
+        }
+    }
+}
+
 impl TransactionModel {
     /// Create a new `TransactionModel`.
     ///
@@ -62,33 +146,136 @@ impl TransactionModel {
     /// `TransactionModel` was first calculated. This is important for recreating past
     /// `TransactionModel`s accurately, as a `TransactionModel`'s contributions
     /// towards a future payment will often begin on the day that the
-    /// `TransactionModel` is calculated.
+    /// `TransactionModel` is calculated. If no `calculation_date` is provided, the
+    /// current system date is used.
     pub fn new(
-        value: Decimal,
+        value: Money,
         min_value: Option<Decimal>,
         frequency: Frequency,
         start_date: Date<Utc>,
         end_date: Option<Date<Utc>>,
         calculation_date: Option<Date<Utc>>,
     ) -> Result<Self, TransactionError> {
-        // Check that we have a valid currency value
-        if value.round_dp(CURRENCY_PRECISION) != value {
-            return Err(TransactionError::CurrencyPrecision(value));
+        match calculation_date {
+            Some(date) => Self::new_with_clock(
+                value,
+                min_value,
+                frequency,
+                start_date,
+                end_date.into(),
+                RoundingPolicy::Exact,
+                &FixedClock::new(date),
+            ),
+            None => Self::new_with_clock(
+                value,
+                min_value,
+                frequency,
+                start_date,
+                end_date.into(),
+                RoundingPolicy::Exact,
+                &SystemClock,
+            ),
         }
+    }
 
-        let now = calculation_date.unwrap_or_else(Utc::today);
-        let contributions = calculate(value, &frequency, start_date, end_date, now)?;
+    /// Create a new `TransactionModel`, reading the calculation date from a [`Clock`].
+    ///
+    /// See [`TransactionModel::new`] for details. The clock is read once, so every
+    /// contribution for this model is calculated against the same date.
+    ///
+    /// Rather than an end date, the `termination` may also limit the model to a number
+    /// of payments, in which case the end date is the date of the final payment. The
+    /// `rounding` policy determines whether daily contributions are rounded to amounts
+    /// that can be transferred.
+    pub fn new_with_clock(
+        value: Money,
+        min_value: Option<Decimal>,
+        frequency: Frequency,
+        start_date: Date<Utc>,
+        termination: Termination,
+        rounding: RoundingPolicy,
+        clock: &dyn Clock,
+    ) -> Result<Self, TransactionError> {
+        let mut model = TransactionModel {
+            value,
+            min_value,
+            contributions: Vec::new(),
+            frequency,
+            start_date,
+            rounding,
+            amounts: None,
+            escalation: None,
+        };
+        model.calculate_contributions(termination, clock.today())?;
+        Ok(model)
+    }
 
-        if contributions.is_empty() {
-            return Err(TransactionError::EmptyContributions);
-        }
+    /// Create a new `TransactionModel` whose payments vary in amount.
+    ///
+    /// Each payment's amount is taken from the `amounts` schedule, falling back to
+    /// `value` for any payment that the schedule doesn't cover. Contributions are
+    /// smoothed across the unequal payments, so larger payments are saved for in advance
+    /// rather than all at once. If the model repeats forever, the contributions settle
+    /// into a regular amount once the last payment in the schedule has been made.
+    ///
+    /// See [`TransactionModel::new_with_clock`] for details of the other arguments.
+    /// Models with variable payments cannot be ameliorated.
+    pub fn new_variable(
+        value: Money,
+        amounts: AmountSchedule,
+        frequency: Frequency,
+        start_date: Date<Utc>,
+        termination: Termination,
+        rounding: RoundingPolicy,
+        clock: &dyn Clock,
+    ) -> Result<Self, TransactionError> {
+        let mut model = TransactionModel {
+            value,
+            min_value: None,
+            contributions: Vec::new(),
+            frequency,
+            start_date,
+            rounding,
+            amounts: Some(amounts),
+            escalation: None,
+        };
+        model.calculate_contributions(termination, clock.today())?;
+        Ok(model)
+    }
 
-        Ok(TransactionModel {
+    /// Create a new `TransactionModel` whose value increases over time according to an
+    /// [`Escalation`] rule, such as rent that rises by a fixed percentage each year.
+    ///
+    /// The `value` is the amount of the payments from `start_date`. Each escalation
+    /// begins a new contribution segment, so the contributions rise with the payments
+    /// rather than being averaged across the model. If the model repeats forever, the
+    /// contributions settle into a regular amount after the last step or index value,
+    /// which means that an [`Escalation::Annual`] model must have an end date or a
+    /// payment count.
+    ///
+    /// See [`TransactionModel::new_with_clock`] for details of the other arguments.
+    /// Escalating models cannot be ameliorated.
+    pub fn new_escalating(
+        value: Money,
+        escalation: Escalation,
+        frequency: Frequency,
+        start_date: Date<Utc>,
+        termination: Termination,
+        rounding: RoundingPolicy,
+        clock: &dyn Clock,
+    ) -> Result<Self, TransactionError> {
+        let mut model = TransactionModel {
             value,
-            min_value,
-            contributions,
+            min_value: None,
+            contributions: Vec::new(),
             frequency,
-        })
+            start_date,
+            rounding,
+            amounts: None,
+            escalation: Some(escalation),
+        };
+        model.calculate_contributions(termination, clock.today())?;
+        Ok(model)
     }
 
     /// Whether this `TransactionModel` can be ameliorated.
@@ -148,13 +335,14 @@ impl TransactionModel {
         }
 
         // Create an ameliorated transaction
-        let ameliorated = TransactionModel::new(
-            target,
+        let ameliorated = TransactionModel::new_with_clock(
+            Money::new(target, self.get_currency()),
             self.min_value,
             self.frequency.clone(),
             start_date,
-            Some(end_date),
-            Some(start_date),
+            Termination::EndDate(end_date),
+            self.rounding,
+            &FixedClock::new(start_date),
         );
 
         // Cache the actual end date to avoid multiple calls to fn
@@ -164,13 +352,14 @@ impl TransactionModel {
         // a new `TransactionModel` to represent the rest of the period.
         let restarted = if self_end_date.is_none() || Some(end_date) < self_end_date {
             let new_start = end_date.succ();
-            Some(TransactionModel::new(
+            Some(TransactionModel::new_with_clock(
                 self.value,
                 self.min_value,
                 self.frequency.clone(),
                 new_start,
-                self_end_date,
-                Some(new_start),
+                self_end_date.into(),
+                self.rounding,
+                &FixedClock::new(new_start),
             ))
         } else {
             None
@@ -182,20 +371,421 @@ impl TransactionModel {
         Some((ameliorated, restarted))
     }
 
-    fn get_start_date(&self) -> Option<Date<Utc>> {
+    /// Re-forecast this model after an actual payment.
+    ///
+    /// The payment settles the expected payment nearest to it, which must be within
+    /// `tolerance` days of the payment, as in
+    /// [`Ledger::reconcile`](crate::ledger::Ledger::reconcile). This model's
+    /// contributions are ended on the expected payment date, then recalculated from
+    /// the next payment onwards, so that any payments that were early or late don't
+    /// disrupt the contributions for the rest of the model.
+    ///
+    /// If the actual payment was more than this model's value, a catch-up
+    /// `TransactionModel` is returned to recoup the shortfall by the next payment date,
+    /// even if this model ends before then. Like any other model, the catch-up model
+    /// should be included in affordability calculations alongside this one. If the
+    /// actual payment was less than this model's value, the surplus is left
+    /// unallocated.
+    pub fn reforecast(
+        &mut self,
+        payment: &Payment,
+        tolerance: Duration,
+    ) -> Result<Option<TransactionModel>, TransactionError> {
+        let actual = payment.get_amount();
+        actual.check_currency(self.get_currency())?;
+        if !actual.is_valid() {
+            return Err(TransactionError::CurrencyPrecision(actual));
+        }
+
+        let date = payment.get_date();
+        let end_date = self.get_end_date();
+        // Find the nearest expected payment, favouring the earlier payment if the actual
+        // payment falls exactly between two. Note that `next` may fall after the end
+        // date, in which case it only serves as a deadline for catching up.
+        let mut expected = None;
+        let mut next = None;
+        for occurrence in self.frequency.occurrences(self.start_date) {
+            match expected {
+                _ if end_date.is_some_and(|e| occurrence > e) => {
+                    next = Some(occurrence);
+                    break;
+                }
+                Some(e) if occurrence == e => continue,
+                Some(e) if occurrence - date >= date - e => {
+                    next = Some(occurrence);
+                    break;
+                }
+                _ => expected = Some(occurrence),
+            }
+        }
+        let expected = expected
+            .filter(|e| (date - *e).num_days().abs() <= tolerance.num_days())
+            .ok_or(TransactionError::UnexpectedPayment(date))?;
+
+        debug!(
+            "reforecasting payment of {} on {} against expected payment on {}",
+            actual, date, expected
+        );
+
+        // End the current contribution on the expected payment date, then calculate
+        // go-forward contributions for the remaining payments
+        self.set_end_date(expected);
+        if let Some(next) = next.filter(|n| end_date.is_none_or(|e| *n <= e)) {
+            let mut go_forward = self.calculate_from(next, end_date, expected.succ())?;
+            self.contributions.append(&mut go_forward);
+        }
+
+        let shortfall = actual.checked_sub(self.get_payment_amount(expected))?;
+        if shortfall.get_amount() <= Decimal::ZERO {
+            return Ok(None);
+        }
+
+        // We can't start catching up until the money has been spent
+        let catch_up_start = date.succ();
+        let due = next.unwrap_or(catch_up_start).max(catch_up_start);
+        debug!("catching up shortfall of {} by {}", shortfall, due);
+
+        TransactionModel::new_with_clock(
+            shortfall,
+            None,
+            Frequency::Once,
+            due,
+            Termination::Never,
+            self.rounding,
+            &FixedClock::new(catch_up_start),
+        )
+        .map(Some)
+    }
+
+    /// The daily contributions for this model from `from` to `to` (inclusive).
+    ///
+    /// Each day's entry includes the amount to set aside, the index of the contribution
+    /// segment that produced it and the payment dates that it funds, i.e. the
+    /// remaining payments in the segment's current period. A model's contributions are
+    /// split into sequential segments when a single daily amount can't cover every
+    /// payment, such as when the first payment falls before a full period of
+    /// contributions. Days without a contribution are omitted.
+    pub fn schedule(&self, from: Date<Utc>, to: Date<Utc>) -> Vec<ScheduledContribution> {
+        let period_end = match self.get_period_end(Some(to)) {
+            Some(date) => date.max(to),
+            None => return Vec::new(),
+        };
+        let payments = self
+            .frequency
+            .get_payment_dates(self.start_date, Some(period_end));
+
+        iter::successors(Some(from), |d| Some(d.succ()))
+            .take_while(|d| *d <= to)
+            .filter_map(|date| {
+                self.contributions
+                    .iter()
+                    .enumerate()
+                    .find_map(|(segment, c)| {
+                        let amount = c.regular_or_last(date)?;
+                        let period_end = c.get_period_end(Some(date));
+
+                        Some(ScheduledContribution {
+                            date,
+                            amount,
+                            segment,
+                            payments: payments
+                                .iter()
+                                .filter(|p| **p >= date && **p <= period_end)
+                                .copied()
+                                .collect(),
+                        })
+                    })
+            })
+            .collect()
+    }
+
+    /// The value of each payment.
+    pub fn get_value(&self) -> Money {
+        self.value
+    }
+
+    /// The currency of this model's payments and contributions.
+    pub fn get_currency(&self) -> Currency {
+        self.value.get_currency()
+    }
+
+    /// The minimum value that each payment can be reduced to, if this model can be
+    /// ameliorated.
+    pub fn get_min_value(&self) -> Option<Decimal> {
+        self.min_value
+    }
+
+    /// The amount of the payment on the given date, assuming that a payment is due on
+    /// that date.
+    pub fn get_payment_amount(&self, date: Date<Utc>) -> Money {
+        let amounts = self
+            .amounts
+            .as_ref()
+            .map(|a| a.get_amounts(&self.frequency, self.start_date))
+            .unwrap_or_default();
+        Money::new(
+            PaymentAmounts::new(self.get_base_value(date), &amounts).get_amount(date),
+            self.get_currency(),
+        )
+    }
+
+    /// The schedule of payment amounts, if they vary between payments.
+    pub fn get_amount_schedule(&self) -> Option<&AmountSchedule> {
+        self.amounts.as_ref()
+    }
+
+    /// The rule used to increase this model's value over time, if any.
+    pub fn get_escalation(&self) -> Option<&Escalation> {
+        self.escalation.as_ref()
+    }
+
+    /// How often payments are made.
+    pub fn get_frequency(&self) -> &Frequency {
+        &self.frequency
+    }
+
+    /// The policy used to round this model's contributions.
+    pub fn get_rounding(&self) -> RoundingPolicy {
+        self.rounding
+    }
+
+    /// The contribution segments for this model, in date order.
+    pub fn get_contributions(&self) -> &[Contribution] {
+        &self.contributions
+    }
+
+    /// The date that this model's payments start from, as given when it was created.
+    /// Payments fall on the `Frequency`'s dates from this date onwards.
+    pub fn get_payment_start_date(&self) -> Date<Utc> {
+        self.start_date
+    }
+
+    /// The first day that contributions are made towards this model's payments. This
+    /// may be earlier than the first payment, as contributions make use of any lead
+    /// time. See [`TransactionModel::get_payment_start_date`] for the date that payments
+    /// start from. Returns `None` if the model has been curtailed to nothing.
+    pub fn get_start_date(&self) -> Option<Date<Utc>> {
         self.contributions
             .iter()
             .min_by_key(|c| c.get_start_date())
             .map(|c| c.get_start_date())
     }
 
-    fn get_end_date(&self) -> Option<Date<Utc>> {
+    /// The last day that contributions are made, or `None` if the model repeats
+    /// forever.
+    pub fn get_end_date(&self) -> Option<Date<Utc>> {
         self.contributions
             .iter()
             .max_by_key(|c| c.get_start_date())
             .and_then(|c| c.get_end_date())
     }
 
+    // Validate this model's payments and calculate its contributions from the start date
+    fn calculate_contributions(
+        &mut self,
+        termination: Termination,
+        now: Date<Utc>,
+    ) -> Result<(), TransactionError> {
+        let end_date = match termination {
+            Termination::Never => None,
+            Termination::EndDate(date) => Some(date),
+            Termination::Count(n) => Some(
+                self.frequency
+                    .get_nth_payment_date(self.start_date, n)
+                    .ok_or(TransactionError::PaymentCount(n))?,
+            ),
+        };
+
+        // Annual escalation never settles into a regular amount, so it can't repeat
+        // forever
+        if end_date.is_none() && matches!(self.escalation, Some(Escalation::Annual(..))) {
+            return Err(TransactionError::UnboundedEscalation);
+        }
+
+        // Check that we have valid currency values
+        let currency = self.get_currency();
+        let invalid = self
+            .get_amounts(end_date)
+            .values()
+            .map(|a| Money::new(*a, currency))
+            .chain(Some(self.value))
+            .find(|m| !m.is_valid());
+        if let Some(value) = invalid {
+            return Err(TransactionError::CurrencyPrecision(value));
+        }
+
+        self.contributions = self.calculate_from(self.start_date, end_date, now)?;
+
+        if self.contributions.is_empty() {
+            return Err(TransactionError::EmptyContributions);
+        }
+
+        Ok(())
+    }
+
+    // Calculate contributions for the payments from `start_date` to `end_date`.
+    //
+    // A repeating contribution assumes that every period's payments are the same, which
+    // isn't true of variable or escalating payments. So if the model repeats forever, we
+    // calculate contributions up to the last payment that varies, then calculate the
+    // repeating contributions from the payment after that.
+    fn calculate_from(
+        &self,
+        start_date: Date<Utc>,
+        end_date: Option<Date<Utc>>,
+        now: Date<Utc>,
+    ) -> Result<Vec<Contribution>, ContributionError> {
+        let amounts = self.get_amounts(end_date);
+        let payments = PaymentAmounts::new(self.get_base_value(start_date), &amounts);
+
+        let last_varied = amounts.keys().next_back().filter(|d| **d >= start_date);
+        let (last_varied, next) = match (end_date, last_varied) {
+            (None, Some(last)) => (
+                *last,
+                self.frequency
+                    .occurrences(self.start_date)
+                    .find(|d| d > last),
+            ),
+            _ => return self.calculate_escalated(payments, start_date, end_date, now),
+        };
+
+        debug!(
+            "calculating variable payments until {}, then regular payments from {:?}",
+            last_varied, next
+        );
+
+        let mut contributions =
+            self.calculate_escalated(payments, start_date, Some(last_varied), now)?;
+        if let Some(next) = next {
+            contributions.append(&mut calculate(
+                self.get_base_value(next).into(),
+                self.rounding,
+                &self.frequency,
+                next,
+                None,
+                now.max(last_varied.succ()),
+            )?);
+        }
+
+        Ok(contributions)
+    }
+
+    // Calculate contributions for the payments from `start_date` to `end_date`, starting
+    // a new contribution segment from the first payment after each escalation. Otherwise
+    // a single contribution would average the escalated payments across the whole model.
+    fn calculate_escalated(
+        &self,
+        payments: PaymentAmounts,
+        mut start_date: Date<Utc>,
+        end_date: Option<Date<Utc>>,
+        mut now: Date<Utc>,
+    ) -> Result<Vec<Contribution>, ContributionError> {
+        let end_date = match end_date {
+            Some(date) => date,
+            None => {
+                return calculate(
+                    payments,
+                    self.rounding,
+                    &self.frequency,
+                    start_date,
+                    None,
+                    now,
+                )
+            }
+        };
+
+        let splits: Vec<Date<Utc>> = self
+            .escalation
+            .iter()
+            .flat_map(|e| e.get_changes(start_date, end_date))
+            .filter_map(|date| {
+                self.frequency
+                    .occurrences(self.start_date)
+                    .find(|d| *d >= date)
+            })
+            .filter(|date| *date > start_date && *date <= end_date)
+            .collect();
+
+        let mut contributions = Vec::new();
+        for split in splits.into_iter().chain(iter::once(end_date.succ())) {
+            // Escalations between the same two payments share a segment
+            if split <= start_date {
+                continue;
+            }
+
+            trace!(
+                "calculating escalated payments from {} to {}",
+                start_date,
+                split.pred()
+            );
+            let mut segment = calculate(
+                payments,
+                self.rounding,
+                &self.frequency,
+                start_date,
+                Some(split.pred()),
+                now,
+            )?;
+
+            // Start contributing to the next segment's payments after the last payment
+            // of this segment
+            if let Some(end) = segment.last().and_then(|c| c.get_end_date()) {
+                now = now.max(end.succ());
+            }
+            contributions.append(&mut segment);
+            start_date = split;
+        }
+
+        Ok(contributions)
+    }
+
+    // Get the amount of every payment that differs from this model's value. Escalated
+    // amounts are included for each payment up to `until`, or up to the last change in
+    // value if `until` is `None`.
+    fn get_amounts(&self, until: Option<Date<Utc>>) -> BTreeMap<Date<Utc>, Decimal> {
+        let mut amounts = self
+            .amounts
+            .as_ref()
+            .map(|a| a.get_amounts(&self.frequency, self.start_date))
+            .unwrap_or_default();
+
+        if let Some(escalation) = &self.escalation {
+            if let Some(until) = until.or_else(|| escalation.get_last_change()) {
+                for date in self
+                    .frequency
+                    .occurrences(self.start_date)
+                    .take_while(|d| *d <= until)
+                {
+                    amounts.entry(date).or_insert_with(|| {
+                        escalation
+                            .escalate(self.value, self.start_date, date)
+                            .get_amount()
+                    });
+                }
+            }
+        }
+
+        amounts
+    }
+
+    // Get this model's value on the given date, after any escalation
+    fn get_base_value(&self, date: Date<Utc>) -> Money {
+        match &self.escalation {
+            Some(escalation) => escalation.escalate(self.value, self.start_date, date),
+            None => self.value,
+        }
+    }
+
+    // Get the dates of this model's payments from `from` to `to` (inclusive)
+    pub(crate) fn get_payment_dates(&self, from: Date<Utc>, to: Date<Utc>) -> Vec<Date<Utc>> {
+        let end_date = self.get_end_date();
+        self.frequency
+            .get_payment_dates(self.start_date, Some(to))
+            .into_iter()
+            .filter(|d| *d >= from && *d <= to && end_date.is_none_or(|e| *d <= e))
+            .collect()
+    }
+
     fn set_end_date(&mut self, end_date: Date<Utc>) {
         // Delete any contributions that start after the end date
         self.contributions.retain(|c| c.get_start_date() < end_date);
@@ -211,18 +801,21 @@ impl TransactionModel {
                 .last()
                 .map(|d| *d);
 
-            // Find the contribution that overlaps the end date, then shorten it.
+            // Find the contribution that overlaps the end date, then shorten it. This
+            // includes a contribution whose period ends on the end date.
             // Note that as contributions for a model are sequential and non-overlapping, we
             // are guaranteed to only ever deal with one contribution that overlaps the new
             // end date.
             self.contributions
                 .iter_mut()
-                .find(|c| c.get_period_end(Some(end_date)) > end_date)
+                .find(|c| c.get_period_end(Some(end_date)) >= end_date)
                 .and_then(|c| Some(c.set_end_date(end_date, last_payment)));
         }
     }
 
-    fn get_period_end(&self, date: Option<Date<Utc>>) -> Option<Date<Utc>> {
+    /// The last day of the contribution period that contains `date`, or of the first
+    /// period of the final contribution segment if no date is given.
+    pub fn get_period_end(&self, date: Option<Date<Utc>>) -> Option<Date<Utc>> {
         self.contributions
             .iter()
             .max_by_key(|c| c.get_start_date())
@@ -231,12 +824,10 @@ impl TransactionModel {
 }
 
 impl<'a> ContributionSign<'a> {
-    pub fn regular_or_last(&self, date: Date<Utc>) -> Option<Decimal> {
+    pub fn regular_or_last(&self, date: Date<Utc>) -> Option<Money> {
         match self {
             ContributionSign::Positive(c) => c.regular_or_last(date),
-            &ContributionSign::Negative(c) => {
-                c.regular_or_last(date).map(|i| i * Decimal::NEGATIVE_ONE)
-            }
+            &ContributionSign::Negative(c) => c.regular_or_last(date).map(|m| -m),
         }
     }
 
@@ -256,6 +847,153 @@ impl<'a> ContributionSign<'a> {
     }
 }
 
+impl ScheduledContribution {
+    /// The day to set this contribution aside.
+    pub fn get_date(&self) -> Date<Utc> {
+        self.date
+    }
+
+    /// The amount to set aside on this day.
+    pub fn get_amount(&self) -> Money {
+        self.amount
+    }
+
+    /// The index of the contribution segment that produced this amount.
+    pub fn get_segment(&self) -> usize {
+        self.segment
+    }
+
+    /// The payment dates that this contribution goes towards, in date order.
+    pub fn get_payments(&self) -> &[Date<Utc>] {
+        &self.payments
+    }
+}
+
+impl AffordabilityReport {
+    fn new(currency: Option<Currency>, days: Vec<(Date<Utc>, Decimal)>) -> Self {
+        let deficits = days.iter().filter(|(_, net)| *net < Decimal::ZERO);
+
+        let first_deficit = deficits.clone().next().map(|(date, _)| *date);
+        let largest_deficit = deficits
+            .clone()
+            .min_by_key(|(_, net)| *net)
+            .map(|(date, net)| (*date, -*net));
+        let cumulative_deficit = -deficits.map(|(_, net)| *net).sum::<Decimal>();
+        let cumulative_surplus = days
+            .iter()
+            .filter(|(_, net)| *net > Decimal::ZERO)
+            .map(|(_, net)| *net)
+            .sum();
+
+        AffordabilityReport {
+            currency,
+            days,
+            first_deficit,
+            largest_deficit,
+            cumulative_deficit,
+            cumulative_surplus,
+        }
+    }
+
+    /// The currency that every amount in this report is in, if there were any models.
+    pub fn get_currency(&self) -> Option<Currency> {
+        self.currency
+    }
+
+    /// The net amount for each day in the affordability window, in date order.
+    pub fn get_days(&self) -> &[(Date<Utc>, Decimal)] {
+        &self.days
+    }
+
+    /// The net amount for the given day, if it falls within the affordability window.
+    pub fn get_net(&self, date: Date<Utc>) -> Option<Decimal> {
+        self.days
+            .binary_search_by_key(&date, |(d, _)| *d)
+            .ok()
+            .map(|i| self.days[i].1)
+    }
+
+    /// The first day with a deficit, if any.
+    pub fn get_first_deficit_date(&self) -> Option<Date<Utc>> {
+        self.first_deficit
+    }
+
+    /// The day with the largest deficit and the amount of that deficit, if any. If
+    /// several days share the largest deficit, the earliest day is returned.
+    pub fn get_largest_deficit(&self) -> Option<(Date<Utc>, Decimal)> {
+        self.largest_deficit
+    }
+
+    /// The total of every day's deficit over the affordability window.
+    pub fn get_cumulative_deficit(&self) -> Decimal {
+        self.cumulative_deficit
+    }
+
+    /// The total of every day's surplus over the affordability window.
+    pub fn get_cumulative_surplus(&self) -> Decimal {
+        self.cumulative_surplus
+    }
+
+    /// The net surplus for each month in the affordability window, dated on the last
+    /// day of that month with a net amount. Each surplus is rounded down to the
+    /// currency's minor unit, so that it can be spent (e.g. with
+    /// [`plan_payoff`](crate::plan_payoff)). Months whose deficits outweigh their
+    /// surpluses are omitted.
+    pub fn get_monthly_surplus(&self) -> Vec<(Date<Utc>, Money)> {
+        let currency = match self.currency {
+            Some(currency) => currency,
+            None => return Vec::new(),
+        };
+
+        let mut months: Vec<(Date<Utc>, Decimal)> = Vec::new();
+        for (date, net) in &self.days {
+            match months.last_mut() {
+                Some((last, total))
+                    if (last.year(), last.month()) == (date.year(), date.month()) =>
+                {
+                    *last = *date;
+                    *total += *net;
+                }
+                _ => months.push((*date, *net)),
+            }
+        }
+
+        months
+            .into_iter()
+            .map(|(date, total)| (date, RoundingPolicy::CarryForward.round(total, currency)))
+            .filter(|(_, total)| *total > Decimal::ZERO)
+            .map(|(date, total)| (date, Money::new(total, currency)))
+            .collect()
+    }
+
+    /// Summarise this report as an [`AffordabilityResult`].
+    pub fn to_result(&self) -> AffordabilityResult {
+        // Accumulate surplus dates
+        let surplus: Vec<Date<Utc>> = self
+            .days
+            .iter()
+            .filter(|(_, dec)| *dec > Decimal::ZERO)
+            .map(|(date, _)| *date)
+            .collect();
+
+        // Accumulate deficit dates
+        let deficit: Vec<Date<Utc>> = self
+            .days
+            .iter()
+            .filter(|(_, dec)| *dec < Decimal::ZERO)
+            .map(|(date, _)| *date)
+            .collect();
+
+        if !deficit.is_empty() {
+            AffordabilityResult::Deficit(deficit, surplus)
+        } else if !surplus.is_empty() {
+            AffordabilityResult::Surplus(surplus)
+        } else {
+            AffordabilityResult::Balanced
+        }
+    }
+}
+
 /// Calculate whether a collection of revenue, expense and savings [`TransactionModel`]s
 /// are sustainable in perpetuity.
 ///
@@ -268,13 +1006,110 @@ impl<'a> ContributionSign<'a> {
 /// rather than a surplus (i.e. `AffordabilityResult::Balanced`). This is optimal because
 /// you have perfectly allocated all revenues to either expenses or savings. No amounts
 /// will be left untracked.
+///
+/// Every model must be in the same currency, otherwise a
+/// [`CurrencyError::Mismatch`] is returned.
 pub fn is_affordable(
     revenues: Option<&[TransactionModel]>,
     expenses: Option<&[TransactionModel]>,
     savings: Option<&[TransactionModel]>,
-) -> AffordabilityResult {
+) -> Result<AffordabilityResult, TransactionError> {
+    Ok(affordability_report(revenues, expenses, savings)?.to_result())
+}
+
+/// Calculate the net amount for each day that a collection of revenue, expense and
+/// savings [`TransactionModel`]s contribute to.
+///
+/// This performs the same calculation as [`is_affordable`], but retains the amounts for
+/// each day so that consumers can determine how far short a budget is, rather than just
+/// whether it is short.
+pub fn affordability_report(
+    revenues: Option<&[TransactionModel]>,
+    expenses: Option<&[TransactionModel]>,
+    savings: Option<&[TransactionModel]>,
+) -> Result<AffordabilityReport, TransactionError> {
     debug!("calculating affordability");
 
+    let currency = get_common_currency(revenues, expenses, savings)?;
+    let contributions = collect_contributions(revenues, expenses, savings);
+
+    // Accumulate totals for each day we have contributions for
+    let day_totals = acc_daily_contributions(&contributions, None);
+
+    Ok(AffordabilityReport::new(
+        currency,
+        day_totals.into_iter().collect(),
+    ))
+}
+
+/// Calculate whether a collection of revenue, expense and savings [`TransactionModel`]s
+/// in any number of currencies are sustainable in perpetuity.
+///
+/// This is the same as [`is_affordable`], except that each day's contributions are
+/// converted into the reporting `currency` at that day's exchange rate.
+pub fn is_affordable_in(
+    currency: Currency,
+    rates: &dyn ExchangeRates,
+    revenues: Option<&[TransactionModel]>,
+    expenses: Option<&[TransactionModel]>,
+    savings: Option<&[TransactionModel]>,
+) -> Result<AffordabilityResult, TransactionError> {
+    Ok(affordability_report_in(currency, rates, revenues, expenses, savings)?.to_result())
+}
+
+/// Calculate the net amount for each day, in the reporting `currency`, that a collection
+/// of revenue, expense and savings [`TransactionModel`]s contribute to.
+///
+/// See [`is_affordable_in`] for details.
+pub fn affordability_report_in(
+    currency: Currency,
+    rates: &dyn ExchangeRates,
+    revenues: Option<&[TransactionModel]>,
+    expenses: Option<&[TransactionModel]>,
+    savings: Option<&[TransactionModel]>,
+) -> Result<AffordabilityReport, TransactionError> {
+    debug!("calculating affordability in {}", currency);
+
+    let contributions = collect_contributions(revenues, expenses, savings);
+    let day_totals = acc_daily_contributions_with(&contributions, None, |money, date| {
+        rates.convert(money, currency, date).map(|m| m.get_amount())
+    })?;
+
+    Ok(AffordabilityReport::new(
+        Some(currency),
+        day_totals.into_iter().collect(),
+    ))
+}
+
+// Get the currency shared by every `TransactionModel`, as amounts in different currencies
+// can't be summed together.
+pub(crate) fn get_common_currency(
+    revenues: Option<&[TransactionModel]>,
+    expenses: Option<&[TransactionModel]>,
+    savings: Option<&[TransactionModel]>,
+) -> Result<Option<Currency>, CurrencyError> {
+    let models = [revenues, expenses, savings];
+    let mut models = models.iter().flat_map(|m| m.unwrap_or_default());
+
+    match models.next() {
+        Some(first) => {
+            let currency = first.get_currency();
+            for model in models {
+                model.get_value().check_currency(currency)?;
+            }
+            Ok(Some(currency))
+        }
+        None => Ok(None),
+    }
+}
+
+// Extract the contributions for each collection of `TransactionModel`s, signed according
+// to whether they add to (revenues) or subtract from (expenses and savings) the total.
+pub(crate) fn collect_contributions<'a>(
+    revenues: Option<&'a [TransactionModel]>,
+    expenses: Option<&'a [TransactionModel]>,
+    savings: Option<&'a [TransactionModel]>,
+) -> Vec<ContributionSign<'a>> {
     let mut contributions = Vec::new();
 
     // Extract revenue contributions
@@ -298,112 +1133,837 @@ pub fn is_affordable(
         .map(ContributionSign::Negative)
         .for_each(|c| contributions.push(c));
 
-    // Accumulate totals for each day we have contributions for
-    let day_totals = acc_daily_contributions(&contributions);
-
-    // Accumulate surplus dates
-    let surplus: Vec<Date<Utc>> = day_totals
-        .iter()
-        .filter(|(_, dec)| **dec > Decimal::ZERO)
-        .map(|(date, _)| *date)
-        .collect();
-
-    // Accumulate deficit dates
-    let deficit: Vec<Date<Utc>> = day_totals
-        .iter()
-        .filter(|(_, dec)| **dec < Decimal::ZERO)
-        .map(|(date, _)| *date)
-        .collect();
-
-    if !deficit.is_empty() {
-        AffordabilityResult::Deficit(deficit, surplus)
-    } else if !surplus.is_empty() {
-        AffordabilityResult::Surplus(surplus)
-    } else {
-        AffordabilityResult::Balanced
-    }
+    contributions
 }
 
-fn acc_daily_contributions(contributions: &[ContributionSign]) -> HashMap<Date<Utc>, Decimal> {
-    let mut day_totals = HashMap::new();
+// Accumulate the total contributions for each day in `range` (inclusive). If no range is
+// provided, we use the range from the earliest contribution to the end of the latest
+// contribution period.
+pub(crate) fn acc_daily_contributions(
+    contributions: &[ContributionSign],
+    range: Option<(Date<Utc>, Date<Utc>)>,
+) -> BTreeMap<Date<Utc>, Decimal> {
+    acc_daily_contributions_with(contributions, range, |money, _| {
+        Ok::<_, Infallible>(money.get_amount())
+    })
+    .unwrap_or_else(|e| match e {})
+}
+
+// Accumulate the total contributions for each day, using `amount` to get the value of
+// each contribution on a given day (e.g. to convert it into another currency).
+fn acc_daily_contributions_with<E, F>(
+    contributions: &[ContributionSign],
+    range: Option<(Date<Utc>, Date<Utc>)>,
+    mut amount: F,
+) -> Result<BTreeMap<Date<Utc>, Decimal>, E>
+where
+    F: FnMut(Money, Date<Utc>) -> Result<Decimal, E>,
+{
+    let mut day_totals = BTreeMap::new();
 
     trace!("provided contributions: {:?}", contributions);
 
-    if let Some(min) = contributions.iter().min_by_key(|c| c.get_start_date()) {
+    let range = range.or_else(|| {
+        let min = contributions.iter().map(|c| c.get_start_date()).min()?;
         // If we have a min, we must have a max, so unwrapping is safe
         let max = contributions
             .iter()
-            .max_by_key(|c| c.get_period_end(None))
+            .map(|c| c.get_period_end(None))
+            .max()
             .unwrap();
+        Some((min, max))
+    });
 
-        trace!(
-            "contribution date range is from {} to {}",
-            min.get_start_date(),
-            max.get_period_end(None)
-        );
+    if let Some((start, end)) = range {
+        trace!("contribution date range is from {} to {}", start, end);
 
-        let mut date = min.get_start_date();
-        while date <= max.get_period_end(None) {
+        let mut date = start;
+        while date <= end {
             // Calculate total for date
-            let total = contributions
-                .iter()
-                .filter_map(|c| c.regular_or_last(date))
-                .fold(Decimal::ZERO, |total, value| total + value);
-
-            trace!(
-                "accumulating date {}: current {} + new {} = {}",
-                date,
-                day_totals.get(&date).unwrap_or(&Decimal::ZERO),
-                total,
-                day_totals.get(&date).unwrap_or(&Decimal::ZERO) + total
-            );
+            let mut total = Decimal::ZERO;
+            for value in contributions.iter().filter_map(|c| c.regular_or_last(date)) {
+                total += amount(value, date)?;
+            }
 
-            // Insert/update total
-            day_totals
-                .entry(date)
-                .and_modify(|mut d| d += total)
-                .or_insert(total);
+            trace!("accumulating date {}: {}", date, total);
 
+            day_totals.insert(date, total);
             date = date.succ();
         }
     }
 
-    day_totals
+    Ok(day_totals)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        business_day::BusinessDayConvention, clock::SteppingClock, exchange::RateTable,
+        holiday::HolidayCalendar,
+    };
     use chrono::{Datelike, TimeZone};
     use rust_decimal_macros::dec;
 
     #[test]
     fn new_transaction_ok() {
-        let start_date = Utc::today();
-        let result = TransactionModel::new(
-            dec!(0.01),
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let start_date = clock.today();
+        let result = TransactionModel::new_with_clock(
+            Money::new(dec!(0.01), Currency::AUD),
             None,
             Frequency::Weekly(1, vec![start_date.weekday().number_from_monday()]),
             start_date,
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn new_transaction_historical() {
+        let clock = SteppingClock::new(Utc.ymd(2000, 4, 1));
+        let start_date = clock.today();
+
+        clock.advance(chrono::Duration::days(1));
+        let result = TransactionModel::new_with_clock(
+            Money::new(dec!(1), Currency::AUD),
             None,
+            Frequency::Daily(1),
+            start_date,
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        );
+        assert_eq!(
+            result,
+            Err(TransactionError::Contribution(
+                ContributionError::HistoricalStartDate
+            ))
+        );
+    }
+
+    #[test]
+    fn new_transaction_precision() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let result = TransactionModel::new_with_clock(
+            Money::new(dec!(0.001), Currency::AUD),
             None,
+            Frequency::Daily(1),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        );
+        assert_eq!(
+            result,
+            Err(TransactionError::CurrencyPrecision(Money::new(
+                dec!(0.001),
+                Currency::AUD
+            )))
         );
-        assert!(result.is_ok());
     }
 
     #[test]
-    fn transaction_ameliorate_no_min() {
-        let start_date = Utc::today();
-        let mut trans =
-            TransactionModel::new(dec!(10), None, Frequency::Daily(2), start_date, None, None)
-                .unwrap();
-        assert!(trans.ameliorate(dec!(6), start_date, start_date).is_none());
+    fn new_transaction_currency_precision() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let kwd = Currency::new("KWD").unwrap();
+        let new = |value, currency| {
+            TransactionModel::new_with_clock(
+                Money::new(value, currency),
+                None,
+                Frequency::Daily(1),
+                clock.today(),
+                Termination::Never,
+                RoundingPolicy::Exact,
+                &clock,
+            )
+        };
+
+        assert!(new(dec!(0.001), kwd).is_ok());
+        assert!(new(dec!(100), Currency::JPY).is_ok());
+
+        let error = new(dec!(100.5), Currency::JPY).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "JPY values cannot have more than 0 decimal places: 100.5"
+        );
     }
 
     #[test]
-    fn transaction_ameliorate_oob() {
+    fn new_transaction_count() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let trans = TransactionModel::new_with_clock(
+            Money::new(dec!(10), Currency::AUD),
+            None,
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Count(3),
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap();
+        assert_eq!(trans.get_end_date(), Some(Utc.ymd(2000, 6, 15)));
+
+        let result = TransactionModel::new_with_clock(
+            Money::new(dec!(10), Currency::AUD),
+            None,
+            Frequency::Once,
+            clock.today(),
+            Termination::Count(2),
+            RoundingPolicy::Exact,
+            &clock,
+        );
+        assert_eq!(result, Err(TransactionError::PaymentCount(2)));
+    }
+
+    #[test]
+    fn new_transaction_count_adjusted() {
+        // The 4th payment on 1 July 2000 is rolled back to Friday 30 June
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let trans = TransactionModel::new_with_clock(
+            Money::new(dec!(10), Currency::AUD),
+            None,
+            Frequency::Adjusted(
+                Box::new(Frequency::MonthlyDate(1, vec![1])),
+                BusinessDayConvention::Preceding,
+                HolidayCalendar::default(),
+            ),
+            clock.today(),
+            Termination::Count(4),
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap();
+        assert_eq!(trans.get_end_date(), Some(Utc.ymd(2000, 6, 30)));
+
+        let total: Decimal = trans
+            .schedule(clock.today(), Utc.ymd(2000, 6, 30))
+            .iter()
+            .map(|c| c.get_amount().get_amount())
+            .sum();
+        assert_eq!(total.round_dp(2), dec!(40));
+    }
+
+    #[test]
+    fn new_transaction_count_following() {
+        // The 3rd payment on 1 July 2000 is rolled forward to Monday 3 July
+        let clock = FixedClock::new(Utc.ymd(2000, 5, 1));
+        let trans = TransactionModel::new_with_clock(
+            Money::new(dec!(10), Currency::AUD),
+            None,
+            Frequency::Adjusted(
+                Box::new(Frequency::MonthlyDate(1, vec![1])),
+                BusinessDayConvention::Following,
+                HolidayCalendar::default(),
+            ),
+            clock.today(),
+            Termination::Count(3),
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap();
+        assert_eq!(trans.get_end_date(), Some(Utc.ymd(2000, 7, 3)));
+
+        let total: Decimal = trans
+            .schedule(clock.today(), Utc.ymd(2000, 7, 3))
+            .iter()
+            .map(|c| c.get_amount().get_amount())
+            .sum();
+        assert_eq!(total.round_dp(2), dec!(30));
+    }
+
+    #[test]
+    fn transaction_accessors() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let frequency = Frequency::MonthlyDate(1, vec![15]);
+        let trans = TransactionModel::new_with_clock(
+            Money::new(dec!(10), Currency::AUD),
+            Some(dec!(5)),
+            frequency.clone(),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::CarryForward,
+            &clock,
+        )
+        .unwrap();
+
+        assert_eq!(trans.get_value(), Money::new(dec!(10), Currency::AUD));
+        assert_eq!(trans.get_min_value(), Some(dec!(5)));
+        assert_eq!(trans.get_frequency(), &frequency);
+        assert_eq!(trans.get_rounding(), RoundingPolicy::CarryForward);
+        assert_eq!(trans.get_start_date(), Some(clock.today()));
+        assert_eq!(trans.get_payment_start_date(), clock.today());
+        assert_eq!(trans.get_end_date(), None);
+        assert_eq!(trans.get_contributions().len(), 8);
+        assert_eq!(
+            trans.get_contributions()[1].get_start_date(),
+            Utc.ymd(2000, 4, 16)
+        );
+    }
+
+    #[test]
+    fn new_transaction_rounding() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let new = |rounding| {
+            TransactionModel::new_with_clock(
+                Money::new(dec!(100), Currency::AUD),
+                None,
+                Frequency::MonthlyDate(1, vec![15]),
+                clock.today(),
+                Termination::Count(3),
+                rounding,
+                &clock,
+            )
+            .unwrap()
+        };
+
+        let days = |trans: &TransactionModel| {
+            let end_date = trans.get_end_date().unwrap();
+            std::iter::successors(Some(clock.today()), |d| Some(d.succ()))
+                .take_while(|d| *d <= end_date)
+                .filter_map(|d| {
+                    trans
+                        .contributions
+                        .iter()
+                        .find_map(|c| c.regular_or_last(d))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let carry = days(&new(RoundingPolicy::CarryForward));
+        assert!(carry.iter().all(|m| m.is_valid()));
+        assert_eq!(
+            carry.iter().map(|m| m.get_amount()).sum::<Decimal>(),
+            dec!(300)
+        );
+
+        let round_up = days(&new(RoundingPolicy::RoundUp));
+        assert!(round_up.iter().all(|m| m.is_valid()));
+        assert!(round_up.iter().map(|m| m.get_amount()).sum::<Decimal>() > dec!(300));
+    }
+
+    #[test]
+    fn schedule_segments() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let trans = TransactionModel::new_with_clock(
+            Money::new(dec!(100), Currency::AUD),
+            None,
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Count(3),
+            RoundingPolicy::CarryForward,
+            &clock,
+        )
+        .unwrap();
+
+        let schedule = trans.schedule(Utc.ymd(2000, 3, 30), Utc.ymd(2000, 6, 30));
+        assert_eq!(schedule.len(), 76);
+        assert_eq!(schedule[0].get_date(), Utc.ymd(2000, 4, 1));
+        assert_eq!(schedule[0].get_segment(), 0);
+        assert_eq!(schedule[0].get_payments(), &[Utc.ymd(2000, 4, 15)]);
+        assert_eq!(schedule[14].get_payments(), &[Utc.ymd(2000, 4, 15)]);
+        assert_eq!(schedule[15].get_segment(), 1);
+        assert_eq!(schedule[15].get_payments(), &[Utc.ymd(2000, 5, 15)]);
+        assert_eq!(schedule[75].get_date(), Utc.ymd(2000, 6, 15));
+        assert_eq!(schedule[75].get_segment(), 2);
+
+        let first_payment: Decimal = schedule[..15]
+            .iter()
+            .map(|s| s.get_amount().get_amount())
+            .sum();
+        assert_eq!(first_payment, dec!(100));
+    }
+
+    #[test]
+    fn schedule_lead_time() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let trans = TransactionModel::new_with_clock(
+            Money::new(dec!(70), Currency::AUD),
+            None,
+            Frequency::Weekly(1, vec![1, 4]),
+            Utc.ymd(2000, 4, 10),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap();
+        assert_eq!(trans.get_payment_start_date(), Utc.ymd(2000, 4, 10));
+        assert!(trans.get_start_date() < Some(Utc.ymd(2000, 4, 10)));
+
+        // Payments on the Monday and Thursday before the start date aren't funded
+        for entry in trans.schedule(Utc.ymd(2000, 4, 1), Utc.ymd(2000, 4, 30)) {
+            assert!(entry
+                .get_payments()
+                .iter()
+                .all(|p| *p >= Utc.ymd(2000, 4, 10)));
+            assert!(entry.get_payments().iter().all(|p| *p >= entry.get_date()));
+            assert!(entry.get_payments().len() <= 2);
+        }
+
+        assert!(trans
+            .schedule(Utc.ymd(2000, 4, 2), Utc.ymd(2000, 4, 1))
+            .is_empty());
+    }
+
+    fn electricity() -> TransactionModel {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        TransactionModel::new_with_clock(
+            Money::new(dec!(200), Currency::AUD),
+            None,
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::CarryForward,
+            &clock,
+        )
+        .unwrap()
+    }
+
+    fn sum_schedule(trans: &TransactionModel, from: Date<Utc>, to: Date<Utc>) -> Decimal {
+        trans
+            .schedule(from, to)
+            .iter()
+            .map(|s| s.get_amount().get_amount())
+            .sum()
+    }
+
+    #[test]
+    fn transaction_reforecast_shortfall() {
+        let mut trans = electricity();
+        let payment = Payment::new(Utc.ymd(2000, 5, 15), Money::new(dec!(240), Currency::AUD));
+        let catch_up = trans
+            .reforecast(&payment, Duration::days(5))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(catch_up.get_value(), Money::new(dec!(40), Currency::AUD));
+        assert_eq!(catch_up.get_start_date(), Some(Utc.ymd(2000, 5, 16)));
+        assert_eq!(catch_up.get_end_date(), Some(Utc.ymd(2000, 6, 15)));
+        assert_eq!(
+            sum_schedule(&catch_up, Utc.ymd(2000, 5, 16), Utc.ymd(2000, 6, 15)),
+            dec!(40)
+        );
+
+        // The go-forward contributions still cover the next payment
+        let schedule = trans.schedule(Utc.ymd(2000, 5, 15), Utc.ymd(2000, 5, 16));
+        assert_eq!(schedule[0].get_payments(), &[Utc.ymd(2000, 5, 15)]);
+        let payments = schedule[1].get_payments();
+        assert_eq!(payments.first(), Some(&Utc.ymd(2000, 6, 15)));
+        assert_eq!(payments.last(), Some(&Utc.ymd(2001, 3, 15)));
+        assert_eq!(
+            sum_schedule(&trans, Utc.ymd(2000, 5, 16), Utc.ymd(2001, 3, 15)),
+            dec!(200) * Decimal::from(payments.len())
+        );
+        assert_eq!(trans.get_end_date(), None);
+    }
+
+    #[test]
+    fn transaction_reforecast_late() {
+        let mut trans = electricity();
+        let payment = Payment::new(Utc.ymd(2000, 5, 18), Money::new(dec!(190), Currency::AUD));
+
+        assert_eq!(trans.reforecast(&payment, Duration::days(5)), Ok(None));
+        assert_eq!(
+            trans
+                .get_contributions()
+                .iter()
+                .filter(|c| c.get_end_date() == Some(Utc.ymd(2000, 5, 15)))
+                .count(),
+            1
+        );
+        assert_eq!(
+            sum_schedule(&trans, Utc.ymd(2000, 5, 16), Utc.ymd(2001, 3, 15)),
+            dec!(2000)
+        );
+    }
+
+    #[test]
+    fn transaction_reforecast_period_end() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let new = |value, frequency| {
+            TransactionModel::new_with_clock(
+                Money::new(value, Currency::AUD),
+                None,
+                frequency,
+                clock.today(),
+                Termination::Never,
+                RoundingPolicy::Exact,
+                &clock,
+            )
+            .unwrap()
+        };
+        let revenues = vec![new(dec!(10), Frequency::Daily(1))];
+        let mut expenses = vec![new(dec!(70), Frequency::Weekly(1, vec![5]))];
+        let before = affordability_report(Some(&revenues), Some(&expenses), None).unwrap();
+
+        // An on-time payment on the last day of a period doesn't change affordability
+        let payment = Payment::new(Utc.ymd(2000, 4, 14), Money::new(dec!(70), Currency::AUD));
+        assert_eq!(
+            expenses[0].reforecast(&payment, Duration::days(5)),
+            Ok(None)
+        );
+        let after = affordability_report(Some(&revenues), Some(&expenses), None).unwrap();
+
+        assert_eq!(after.to_result(), before.to_result());
+        assert!(after.get_days().iter().all(|(_, net)| net.is_zero()));
+        assert_eq!(after.get_net(Utc.ymd(2000, 4, 15)), Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn transaction_reforecast_unexpected() {
+        let mut trans = electricity();
+        let payment = Payment::new(Utc.ymd(2000, 8, 1), Money::new(dec!(200), Currency::AUD));
+        assert_eq!(
+            trans.reforecast(&payment, Duration::days(5)),
+            Err(TransactionError::UnexpectedPayment(Utc.ymd(2000, 8, 1)))
+        );
+        assert_eq!(trans, electricity());
+
+        let payment = Payment::new(Utc.ymd(1999, 4, 15), Money::new(dec!(200), Currency::AUD));
+        assert_eq!(
+            trans.reforecast(&payment, Duration::days(5)),
+            Err(TransactionError::UnexpectedPayment(Utc.ymd(1999, 4, 15)))
+        );
+    }
+
+    #[test]
+    fn transaction_reforecast_fixed_end() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let mut trans = TransactionModel::new_with_clock(
+            Money::new(dec!(200), Currency::AUD),
+            None,
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Count(2),
+            RoundingPolicy::CarryForward,
+            &clock,
+        )
+        .unwrap();
+
+        // Settling the final payment leaves nothing to go forward with
+        let payment = Payment::new(Utc.ymd(2000, 5, 15), Money::new(dec!(210), Currency::AUD));
+        let catch_up = trans
+            .reforecast(&payment, Duration::days(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(trans.get_end_date(), Some(Utc.ymd(2000, 5, 15)));
+        assert_eq!(catch_up.get_end_date(), Some(Utc.ymd(2000, 6, 15)));
+
+        let payment = Payment::new(Utc.ymd(2000, 5, 15), Money::new(dec!(210), Currency::USD));
+        assert_eq!(
+            trans.reforecast(&payment, Duration::days(5)),
+            Err(TransactionError::Currency(CurrencyError::Mismatch(
+                Currency::USD,
+                Currency::AUD
+            )))
+        );
+    }
+
+    // Check that by every payment date, the model has contributed enough to cover every
+    // payment so far
+    fn assert_covered(trans: &TransactionModel, to: Date<Utc>) {
+        let start = trans.get_start_date().unwrap();
+        let mut contributed = Decimal::ZERO;
+        let mut paid = Decimal::ZERO;
+        for entry in trans.schedule(start, to) {
+            contributed += entry.get_amount().get_amount();
+            if trans
+                .get_payment_dates(entry.get_date(), entry.get_date())
+                .len()
+                == 1
+            {
+                paid += trans.get_payment_amount(entry.get_date()).get_amount();
+                assert!(
+                    contributed >= paid,
+                    "contributed {} < paid {} on {}",
+                    contributed,
+                    paid,
+                    entry.get_date()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn new_transaction_rounding_covered() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        // Rounded contributions can be transferred as-is, and still cover every payment
+        let policies = [
+            RoundingPolicy::CarryForward,
+            RoundingPolicy::RoundUp,
+            RoundingPolicy::Bankers,
+        ];
+        let frequencies = [
+            (dec!(10), Frequency::MonthlyDate(1, vec![1])),
+            (dec!(1), Frequency::MonthlyDate(12, vec![1])),
+        ];
+
+        for rounding in policies {
+            for (value, frequency) in frequencies.iter() {
+                let trans = TransactionModel::new_with_clock(
+                    Money::new(*value, Currency::AUD),
+                    None,
+                    frequency.clone(),
+                    clock.today(),
+                    Termination::Never,
+                    rounding,
+                    &clock,
+                )
+                .unwrap();
+
+                let to = Utc.ymd(2008, 4, 1);
+                assert_covered(&trans, to);
+                assert!(trans
+                    .schedule(clock.today(), to)
+                    .iter()
+                    .all(|s| s.get_amount().is_valid()));
+            }
+        }
+    }
+
+    #[test]
+    fn new_transaction_rounding_currency() {
+        // Contributions are rounded to each currency's own minor unit
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let kwd = Currency::new("KWD").unwrap();
+        for (value, places) in [
+            (Money::new(dec!(1000), Currency::JPY), 0),
+            (Money::new(dec!(10), kwd), 3),
+        ] {
+            let trans = TransactionModel::new_with_clock(
+                value,
+                None,
+                Frequency::MonthlyDate(1, vec![1]),
+                clock.today(),
+                Termination::Never,
+                RoundingPolicy::CarryForward,
+                &clock,
+            )
+            .unwrap();
+
+            let to = Utc.ymd(2001, 4, 1);
+            assert_covered(&trans, to);
+            let schedule = trans.schedule(clock.today(), to);
+            assert!(schedule.iter().all(|s| s.get_amount().is_valid()));
+            assert!(schedule
+                .iter()
+                .any(|s| s.get_amount().get_amount().scale() == places));
+        }
+    }
+
+    #[test]
+    fn new_transaction_variable_dates() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let amounts = AmountSchedule::Dates(
+            vec![
+                (Utc.ymd(2000, 7, 15), dec!(300)),
+                (Utc.ymd(2000, 10, 15), dec!(150)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let trans = TransactionModel::new_variable(
+            Money::new(dec!(200), Currency::AUD),
+            amounts,
+            Frequency::MonthlyDate(3, vec![15]),
+            clock.today(),
+            Termination::Count(4),
+            RoundingPolicy::CarryForward,
+            &clock,
+        )
+        .unwrap();
+
+        assert_eq!(
+            trans.get_payment_amount(Utc.ymd(2000, 7, 15)),
+            Money::new(dec!(300), Currency::AUD)
+        );
+        assert_eq!(
+            trans.get_payment_amount(Utc.ymd(2001, 1, 15)),
+            Money::new(dec!(200), Currency::AUD)
+        );
+        assert_covered(&trans, Utc.ymd(2001, 1, 15));
+        assert_eq!(
+            sum_schedule(&trans, clock.today(), Utc.ymd(2001, 1, 15)),
+            dec!(850)
+        );
+    }
+
+    #[test]
+    fn new_transaction_variable_repeating() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let trans = TransactionModel::new_variable(
+            Money::new(dec!(20), Currency::AUD),
+            AmountSchedule::from_fn(3, |_| dec!(10)),
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::RoundUp,
+            &clock,
+        )
+        .unwrap();
+
+        // After the introductory price, contributions repeat forever
+        let last = trans.get_contributions().last().unwrap();
+        assert_eq!(last.get_end_date(), None);
+        assert!(last.get_start_date() > Utc.ymd(2000, 6, 15));
+        assert_eq!(trans.get_end_date(), None);
+        assert_covered(&trans, Utc.ymd(2001, 6, 15));
+    }
+
+    #[test]
+    fn new_transaction_variable_precision() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let result = TransactionModel::new_variable(
+            Money::new(dec!(20), Currency::AUD),
+            AmountSchedule::Occurrences(vec![dec!(10.001)]),
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        );
+
+        assert_eq!(
+            result,
+            Err(TransactionError::CurrencyPrecision(Money::new(
+                dec!(10.001),
+                Currency::AUD
+            )))
+        );
+    }
+
+    #[test]
+    fn new_transaction_escalating_annual() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let trans = TransactionModel::new_escalating(
+            Money::new(dec!(1000), Currency::AUD),
+            Escalation::Annual(dec!(3), Utc.ymd(2000, 7, 1)),
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::EndDate(Utc.ymd(2002, 3, 31)),
+            RoundingPolicy::RoundUp,
+            &clock,
+        )
+        .unwrap();
+
+        assert_eq!(
+            trans.get_payment_amount(Utc.ymd(2000, 6, 15)),
+            Money::new(dec!(1000), Currency::AUD)
+        );
+        assert_eq!(
+            trans.get_payment_amount(Utc.ymd(2001, 7, 15)),
+            Money::new(dec!(1060.90), Currency::AUD)
+        );
+        // A new segment starts after the last payment before each anniversary
+        let starts: Vec<Date<Utc>> = trans
+            .get_contributions()
+            .iter()
+            .map(|c| c.get_start_date())
+            .collect();
+        assert!(starts.contains(&Utc.ymd(2000, 6, 16)));
+        assert!(starts.contains(&Utc.ymd(2001, 6, 16)));
+        assert_covered(&trans, Utc.ymd(2002, 3, 31));
+
+        let result = TransactionModel::new_escalating(
+            Money::new(dec!(1000), Currency::AUD),
+            Escalation::Annual(dec!(3), Utc.ymd(2000, 7, 1)),
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::RoundUp,
+            &clock,
+        );
+        assert_eq!(result, Err(TransactionError::UnboundedEscalation));
+    }
+
+    #[test]
+    fn new_transaction_escalating_segments() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let trans = TransactionModel::new_escalating(
+            Money::new(dec!(1000), Currency::AUD),
+            Escalation::Annual(dec!(10), Utc.ymd(2001, 4, 1)),
+            Frequency::MonthlyDate(1, vec![1]),
+            clock.today(),
+            Termination::EndDate(Utc.ymd(2002, 3, 31)),
+            RoundingPolicy::CarryForward,
+            &clock,
+        )
+        .unwrap();
+
+        // Contributions for the escalated payments start after the last payment at the
+        // original value, rather than being averaged across the model
+        let contributions = trans.get_contributions();
+        let split = contributions
+            .iter()
+            .position(|c| c.get_start_date() == Utc.ymd(2001, 3, 2))
+            .unwrap();
+        assert_eq!(
+            contributions[split - 1].get_end_date(),
+            Some(Utc.ymd(2001, 3, 1))
+        );
+        assert_eq!(
+            sum_schedule(&trans, clock.today(), Utc.ymd(2001, 3, 1)),
+            dec!(12000)
+        );
+        assert_eq!(
+            sum_schedule(&trans, Utc.ymd(2001, 3, 2), Utc.ymd(2002, 3, 1)),
+            dec!(13200)
+        );
+        assert_covered(&trans, Utc.ymd(2002, 3, 31));
+    }
+
+    #[test]
+    fn new_transaction_escalating_steps() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let steps = vec![
+            (Utc.ymd(2000, 7, 1), dec!(1100)),
+            (Utc.ymd(2001, 1, 1), dec!(1150)),
+        ];
+        let trans = TransactionModel::new_escalating(
+            Money::new(dec!(1000), Currency::AUD),
+            Escalation::Steps(steps.into_iter().collect()),
+            Frequency::MonthlyDate(1, vec![15]),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::RoundUp,
+            &clock,
+        )
+        .unwrap();
+
+        // After the last step, contributions repeat forever at the final value
+        let last = trans.get_contributions().last().unwrap();
+        assert_eq!(last.get_end_date(), None);
+        assert!(last.get_start_date() > Utc.ymd(2000, 12, 15));
+        assert_eq!(
+            trans.get_payment_amount(Utc.ymd(2005, 1, 15)),
+            Money::new(dec!(1150), Currency::AUD)
+        );
+        assert_covered(&trans, Utc.ymd(2001, 12, 15));
+    }
+
+    #[test]
+    fn transaction_ameliorate_no_min() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let start_date = clock.today();
+        let mut trans = TransactionModel::new_with_clock(
+            Money::new(dec!(10), Currency::AUD),
+            None,
+            Frequency::Daily(2),
+            start_date,
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap();
+        assert!(trans.ameliorate(dec!(6), start_date, start_date).is_none());
+    }
+
+    #[test]
+    fn transaction_ameliorate_oob() {
         let mut trans = TransactionModel::new(
-            dec!(10),
+            Money::new(dec!(10), Currency::AUD),
             Some(dec!(5)),
             Frequency::Daily(2),
             Utc.ymd(2000, 4, 1),
@@ -416,7 +1976,7 @@ mod tests {
         let t1 = t1.expect("Failed to create TransactionModel");
 
         assert_eq!(trans.get_period_end(None), None);
-        assert_eq!(t1.value, dec!(5));
+        assert_eq!(t1.value.get_amount(), dec!(5));
         assert_eq!(t1.min_value, Some(dec!(5)));
         assert_eq!(t1.get_start_date(), Some(Utc.ymd(2000, 4, 1)));
         assert_eq!(t1.get_period_end(None), Some(Utc.ymd(2000, 4, 5)));
@@ -426,7 +1986,7 @@ mod tests {
     #[test]
     fn transaction_ameliorate_no_restart() {
         let mut trans = TransactionModel::new(
-            dec!(10),
+            Money::new(dec!(10), Currency::AUD),
             Some(dec!(5)),
             Frequency::Daily(2),
             Utc.ymd(2000, 4, 1),
@@ -439,7 +1999,7 @@ mod tests {
         let t1 = t1.expect("Failed to create TransactionModel");
 
         assert_eq!(trans.get_period_end(None), Some(Utc.ymd(2000, 5, 17)));
-        assert_eq!(t1.value, dec!(6));
+        assert_eq!(t1.value.get_amount(), dec!(6));
         assert_eq!(t1.min_value, Some(dec!(5)));
         assert_eq!(t1.get_start_date(), Some(Utc.ymd(2000, 5, 18)));
         assert_eq!(t1.get_period_end(None), Some(Utc.ymd(2000, 5, 30)));
@@ -449,7 +2009,7 @@ mod tests {
     #[test]
     fn transaction_ameliorate_with_restart_fixed() {
         let mut trans = TransactionModel::new(
-            dec!(10),
+            Money::new(dec!(10), Currency::AUD),
             Some(dec!(5)),
             Frequency::Daily(2),
             Utc.ymd(2000, 4, 1),
@@ -465,11 +2025,11 @@ mod tests {
             .expect("Failed to create TransactionModel");
 
         assert_eq!(trans.get_period_end(None), Some(Utc.ymd(2000, 4, 30)));
-        assert_eq!(t1.value, dec!(6));
+        assert_eq!(t1.value.get_amount(), dec!(6));
         assert_eq!(t1.min_value, Some(dec!(5)));
         assert_eq!(t1.get_start_date(), Some(Utc.ymd(2000, 5, 1)));
         assert_eq!(t1.get_period_end(None), Some(Utc.ymd(2000, 5, 17)));
-        assert_eq!(t2.value, dec!(10));
+        assert_eq!(t2.value.get_amount(), dec!(10));
         assert_eq!(t2.min_value, Some(dec!(5)));
         assert_eq!(t2.get_start_date(), Some(Utc.ymd(2000, 5, 19)));
         assert_eq!(t2.get_period_end(None), Some(Utc.ymd(2000, 5, 31)));
@@ -478,7 +2038,7 @@ mod tests {
     #[test]
     fn transaction_ameliorate_with_restart_infinite() {
         let mut trans = TransactionModel::new(
-            dec!(10),
+            Money::new(dec!(10), Currency::AUD),
             Some(dec!(5)),
             Frequency::Daily(2),
             Utc.ymd(2000, 4, 1),
@@ -494,11 +2054,11 @@ mod tests {
             .expect("Failed to create TransactionModel");
 
         assert_eq!(trans.get_period_end(None), Some(Utc.ymd(2000, 4, 30)));
-        assert_eq!(t1.value, dec!(6));
+        assert_eq!(t1.value.get_amount(), dec!(6));
         assert_eq!(t1.min_value, Some(dec!(5)));
         assert_eq!(t1.get_start_date(), Some(Utc.ymd(2000, 5, 1)));
         assert_eq!(t1.get_period_end(None), Some(Utc.ymd(2000, 5, 17)));
-        assert_eq!(t2.value, dec!(10));
+        assert_eq!(t2.value.get_amount(), dec!(10));
         assert_eq!(t2.min_value, Some(dec!(5)));
         assert_eq!(t2.get_start_date(), Some(Utc.ymd(2000, 5, 19)));
         assert_eq!(
@@ -513,7 +2073,7 @@ mod tests {
         let start = Utc.ymd(2000, 4, 7);
 
         let revenues = vec![TransactionModel::new(
-            dec!(14),
+            Money::new(dec!(14), Currency::AUD),
             None,
             Frequency::Weekly(1, vec![4]),
             start,
@@ -523,7 +2083,7 @@ mod tests {
         .unwrap()];
 
         let expenses = vec![TransactionModel::new(
-            dec!(1),
+            Money::new(dec!(1), Currency::AUD),
             None,
             Frequency::Daily(1),
             start,
@@ -533,7 +2093,7 @@ mod tests {
         .unwrap()];
 
         let savings = vec![TransactionModel::new(
-            dec!(1),
+            Money::new(dec!(1), Currency::AUD),
             None,
             Frequency::Daily(1),
             start,
@@ -544,7 +2104,7 @@ mod tests {
 
         assert_eq!(
             is_affordable(Some(&revenues), Some(&expenses), Some(&savings)),
-            AffordabilityResult::Balanced
+            Ok(AffordabilityResult::Balanced)
         );
     }
 
@@ -554,7 +2114,7 @@ mod tests {
         let start = Utc.ymd(2000, 4, 7);
 
         let revenues = vec![TransactionModel::new(
-            dec!(14),
+            Money::new(dec!(14), Currency::AUD),
             None,
             Frequency::Weekly(1, vec![4]),
             start,
@@ -564,7 +2124,7 @@ mod tests {
         .unwrap()];
 
         let expenses = vec![TransactionModel::new(
-            dec!(1),
+            Money::new(dec!(1), Currency::AUD),
             None,
             Frequency::Daily(1),
             start,
@@ -574,15 +2134,29 @@ mod tests {
         .unwrap()];
 
         let savings = vec![
-            TransactionModel::new(dec!(1), None, Frequency::Daily(1), start, None, Some(today))
-                .unwrap(),
-            TransactionModel::new(dec!(2), None, Frequency::Once, start, None, Some(start))
-                .unwrap(),
+            TransactionModel::new(
+                Money::new(dec!(1), Currency::AUD),
+                None,
+                Frequency::Daily(1),
+                start,
+                None,
+                Some(today),
+            )
+            .unwrap(),
+            TransactionModel::new(
+                Money::new(dec!(2), Currency::AUD),
+                None,
+                Frequency::Once,
+                start,
+                None,
+                Some(start),
+            )
+            .unwrap(),
         ];
 
         assert_eq!(
             is_affordable(Some(&revenues), Some(&expenses), Some(&savings)),
-            AffordabilityResult::Deficit(vec![start], Vec::new())
+            Ok(AffordabilityResult::Deficit(vec![start], Vec::new()))
         );
     }
 
@@ -592,7 +2166,7 @@ mod tests {
         let start = Utc.ymd(2000, 4, 7);
 
         let revenues = vec![TransactionModel::new(
-            dec!(14),
+            Money::new(dec!(14), Currency::AUD),
             None,
             Frequency::Weekly(1, vec![4]),
             start,
@@ -602,7 +2176,7 @@ mod tests {
         .unwrap()];
 
         let expenses = vec![TransactionModel::new(
-            dec!(1),
+            Money::new(dec!(1), Currency::AUD),
             None,
             Frequency::Daily(1),
             start.succ(),
@@ -612,7 +2186,7 @@ mod tests {
         .unwrap()];
 
         let savings = vec![TransactionModel::new(
-            dec!(1),
+            Money::new(dec!(1), Currency::AUD),
             None,
             Frequency::Daily(1),
             start,
@@ -623,7 +2197,218 @@ mod tests {
 
         assert_eq!(
             is_affordable(Some(&revenues), Some(&expenses), Some(&savings)),
-            AffordabilityResult::Surplus(vec![start])
+            Ok(AffordabilityResult::Surplus(vec![start]))
+        );
+    }
+
+    #[test]
+    fn is_affordable_mixed_currencies() {
+        let start = Utc.ymd(2000, 4, 1);
+        let daily = |currency| {
+            TransactionModel::new(
+                Money::new(dec!(1), currency),
+                None,
+                Frequency::Daily(1),
+                start,
+                None,
+                Some(start),
+            )
+            .unwrap()
+        };
+        let revenues = vec![daily(Currency::AUD)];
+        let expenses = vec![daily(Currency::USD)];
+
+        assert_eq!(
+            is_affordable(Some(&revenues), Some(&expenses), None),
+            Err(TransactionError::Currency(CurrencyError::Mismatch(
+                Currency::USD,
+                Currency::AUD
+            )))
+        );
+        assert_eq!(
+            affordability_report(Some(&revenues), None, None)
+                .unwrap()
+                .get_currency(),
+            Some(Currency::AUD)
+        );
+    }
+
+    #[test]
+    fn is_affordable_in_reporting_currency() {
+        let start = Utc.ymd(2000, 4, 1);
+        let daily = |value, currency| {
+            TransactionModel::new(
+                Money::new(value, currency),
+                None,
+                Frequency::Daily(1),
+                start,
+                Some(start.succ()),
+                Some(start),
+            )
+            .unwrap()
+        };
+        let revenues = vec![daily(dec!(10), Currency::USD)];
+        let expenses = vec![daily(dec!(15), Currency::AUD)];
+
+        // USD 10 buys AUD 16 on the first day, but only AUD 14 on the second
+        let mut rates = RateTable::new();
+        rates.insert(Currency::USD, Currency::AUD, start, dec!(1.6));
+        rates.insert(Currency::USD, Currency::AUD, start.succ(), dec!(1.4));
+
+        let report = affordability_report_in(
+            Currency::AUD,
+            &rates,
+            Some(&revenues),
+            Some(&expenses),
+            None,
+        )
+        .unwrap();
+        assert_eq!(report.get_currency(), Some(Currency::AUD));
+        assert_eq!(
+            report.get_days(),
+            &[(start, dec!(1)), (start.succ(), dec!(-1))]
+        );
+
+        assert_eq!(
+            is_affordable_in(Currency::EUR, &rates, Some(&revenues), None, None),
+            Err(TransactionError::Exchange(ExchangeError::MissingRate(
+                Currency::USD,
+                Currency::EUR,
+                start
+            )))
+        );
+    }
+
+    #[test]
+    fn affordability_report_deficit() {
+        let today = Utc.ymd(2000, 4, 1);
+        let start = Utc.ymd(2000, 4, 3);
+
+        let revenues = vec![TransactionModel::new(
+            Money::new(dec!(2), Currency::AUD),
+            None,
+            Frequency::Daily(1),
+            today,
+            None,
+            Some(today),
+        )
+        .unwrap()];
+
+        let expenses = vec![
+            TransactionModel::new(
+                Money::new(dec!(1), Currency::AUD),
+                None,
+                Frequency::Daily(1),
+                today,
+                None,
+                Some(today),
+            )
+            .unwrap(),
+            TransactionModel::new(
+                Money::new(dec!(6), Currency::AUD),
+                None,
+                Frequency::Once,
+                start,
+                None,
+                Some(today),
+            )
+            .unwrap(),
+        ];
+
+        let report = affordability_report(Some(&revenues), Some(&expenses), None).unwrap();
+
+        assert_eq!(
+            report.get_days(),
+            &[
+                (today, dec!(-1)),
+                (Utc.ymd(2000, 4, 2), dec!(-1)),
+                (start, dec!(-1))
+            ]
         );
+        assert_eq!(report.get_net(start), Some(dec!(-1)));
+        assert_eq!(report.get_first_deficit_date(), Some(today));
+        assert_eq!(report.get_largest_deficit(), Some((today, dec!(1))));
+        assert_eq!(report.get_cumulative_deficit(), dec!(3));
+        assert_eq!(report.get_cumulative_surplus(), Decimal::ZERO);
+        assert_eq!(
+            report.to_result(),
+            AffordabilityResult::Deficit(vec![today, Utc.ymd(2000, 4, 2), start], Vec::new())
+        );
+    }
+
+    #[test]
+    fn affordability_report_surplus() {
+        let today = Utc.ymd(2000, 4, 1);
+
+        let revenues = vec![TransactionModel::new(
+            Money::new(dec!(3), Currency::AUD),
+            None,
+            Frequency::Daily(1),
+            today,
+            None,
+            Some(today),
+        )
+        .unwrap()];
+
+        let report = affordability_report(Some(&revenues), None, None).unwrap();
+
+        assert_eq!(report.get_first_deficit_date(), None);
+        assert_eq!(report.get_largest_deficit(), None);
+        assert_eq!(report.get_cumulative_deficit(), Decimal::ZERO);
+        assert_eq!(report.get_cumulative_surplus(), dec!(3));
+        assert_eq!(
+            report.get_monthly_surplus(),
+            vec![(today, Money::new(dec!(3), Currency::AUD))]
+        );
+        assert_eq!(
+            report.to_result(),
+            AffordabilityResult::Surplus(vec![today])
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn transaction_serde_round_trip() {
+        let model = TransactionModel::new(
+            Money::new(dec!(5.00), Currency::AUD),
+            Some(dec!(2.50)),
+            Frequency::Yearly(1, vec![2, 8], None, None),
+            Utc.ymd(2000, 1, 1),
+            None,
+            Some(Utc.ymd(2000, 1, 1)),
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&model).unwrap();
+        let restored: TransactionModel = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, model);
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn transaction_serde_variable() {
+        let clock = FixedClock::new(Utc.ymd(2000, 4, 1));
+        let model = TransactionModel::new_variable(
+            Money::new(dec!(200), Currency::AUD),
+            AmountSchedule::Dates(
+                vec![(Utc.ymd(2000, 7, 15), dec!(300))]
+                    .into_iter()
+                    .collect(),
+            ),
+            Frequency::MonthlyDate(3, vec![15]),
+            clock.today(),
+            Termination::Never,
+            RoundingPolicy::Exact,
+            &clock,
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(json.contains(r#""Dates":{"2000-07-15":"#));
+
+        let restored: TransactionModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, model);
     }
 }